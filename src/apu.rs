@@ -0,0 +1,164 @@
+// Sound registers without the sound output: values are stored and read
+// back the way the hardware masks them, and the frame sequencer runs the
+// length counters so NR52 reports which channels are still playing.
+
+// Bits that always read back as 1, 0xFF10 - 0xFF26.
+const READ_MASKS: [u8; 0x17] = [
+    0x80, 0x3F, 0x00, 0xFF, 0xBF, // NR10 - NR14
+    0xFF, 0x3F, 0x00, 0xFF, 0xBF, // unused, NR21 - NR24
+    0x7F, 0xFF, 0x9F, 0xFF, 0xBF, // NR30 - NR34
+    0xFF, 0xFF, 0x00, 0x00, 0xBF, // unused, NR41 - NR44
+    0x00, 0x00, 0x70, // NR50 - NR52
+];
+
+const NR52: u16 = 0xFF26;
+
+// DIV bit whose falling edge steps the frame sequencer (512 Hz).
+const FRAME_SEQUENCER_BIT: u16 = 12;
+
+// Longest length per channel, the wave channel's counter is 8 bits.
+const MAX_LENGTHS: [u16; 4] = [64, 64, 256, 64];
+
+pub struct ApuContext {
+    regs: [u8; 0x17],
+    pub wave_ram: [u8; 0x10],
+    // NR52 bits 0-3, the channels that are playing.
+    pub channels: u8,
+    lengths: [u16; 4],
+    sequencer_step: u8,
+    div_bit: bool,
+}
+
+impl Default for ApuContext {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+// Channel an NRxy register belongs to, 0xFF10 - 0xFF23.
+fn apu_channel(address: u16) -> usize {
+    ((address - 0xFF10) / 5) as usize
+}
+
+impl ApuContext {
+    pub fn new() -> Self {
+        let mut apu = ApuContext {
+            regs: [0; 0x17],
+            wave_ram: [0; 0x10],
+            channels: 0,
+            lengths: [0; 4],
+            sequencer_step: 0,
+            div_bit: false,
+        };
+        apu.regs[(NR52 - 0xFF10) as usize] = 0x80;
+        apu
+    }
+
+    fn apu_powered(&self) -> bool {
+        self.regs[(NR52 - 0xFF10) as usize] & 0x80 != 0
+    }
+
+    fn apu_reg(&self, address: u16) -> u8 {
+        self.regs[(address - 0xFF10) as usize]
+    }
+
+    // NR12/NR22/NR42 volume and envelope, or NR30 bit 7 for the wave
+    // channel. A channel with its DAC off can't play.
+    fn apu_dac_enabled(&self, channel: usize) -> bool {
+        match channel {
+            2 => self.apu_reg(0xFF1A) & 0x80 != 0,
+            _ => self.apu_reg(0xFF12 + channel as u16 * 5) & 0xF8 != 0,
+        }
+    }
+
+    // Length counters are clocked on every other step of the sequencer.
+    fn apu_length_clock(&mut self) {
+        for channel in 0..4 {
+            let nrx4 = self.apu_reg(0xFF14 + channel as u16 * 5);
+            if nrx4 & 0x40 == 0 || self.lengths[channel] == 0 {
+                continue;
+            }
+
+            self.lengths[channel] -= 1;
+            if self.lengths[channel] == 0 {
+                self.channels &= !(1 << channel);
+            }
+        }
+    }
+
+    // Advances the APU by one T-cycle, `div` is the timer's internal counter.
+    pub fn apu_tick(&mut self, div: u16) {
+        let div_bit = div & (1 << FRAME_SEQUENCER_BIT) != 0;
+        let falling = self.div_bit && !div_bit;
+        self.div_bit = div_bit;
+
+        if !falling || !self.apu_powered() {
+            return;
+        }
+
+        if self.sequencer_step & 1 == 0 {
+            self.apu_length_clock();
+        }
+        self.sequencer_step = (self.sequencer_step + 1) % 8;
+    }
+
+    pub fn apu_read(&self, address: u16) -> u8 {
+        match address {
+            NR52 => self.apu_reg(NR52) | READ_MASKS[0x16] | self.channels,
+            0xFF10..=0xFF25 => {
+                let i = (address - 0xFF10) as usize;
+                self.regs[i] | READ_MASKS[i]
+            }
+            0xFF30..=0xFF3F => self.wave_ram[(address - 0xFF30) as usize],
+            _ => 0xFF,
+        }
+    }
+
+    fn apu_channel_write(&mut self, address: u16, value: u8) {
+        let channel = apu_channel(address);
+        let max = MAX_LENGTHS[channel];
+
+        match (address - 0xFF10) % 5 {
+            // NRx1, the length is loaded inverted.
+            1 => self.lengths[channel] = max - (value as u16 & (max - 1)),
+            // NRx4 bit 7 triggers the channel.
+            4 if value & 0x80 != 0 => {
+                if self.lengths[channel] == 0 {
+                    self.lengths[channel] = max;
+                }
+                if self.apu_dac_enabled(channel) {
+                    self.channels |= 1 << channel;
+                }
+            }
+            _ => (),
+        }
+
+        if !self.apu_dac_enabled(channel) {
+            self.channels &= !(1 << channel);
+        }
+    }
+
+    pub fn apu_write(&mut self, address: u16, value: u8) {
+        match address {
+            // Turning the APU off clears every register, and while it's off
+            // only NR52 can be written.
+            NR52 => {
+                if value & 0x80 == 0 {
+                    self.regs = [0; 0x17];
+                    self.channels = 0;
+                } else if !self.apu_powered() {
+                    self.regs[(NR52 - 0xFF10) as usize] = 0x80;
+                    self.sequencer_step = 0;
+                }
+            }
+            0xFF10..=0xFF25 if self.apu_powered() => {
+                self.regs[(address - 0xFF10) as usize] = value;
+                if address <= 0xFF23 {
+                    self.apu_channel_write(address, value);
+                }
+            }
+            0xFF30..=0xFF3F => self.wave_ram[(address - 0xFF30) as usize] = value,
+            _ => (),
+        }
+    }
+}
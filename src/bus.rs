@@ -1,6 +1,63 @@
-use crate::cart::CartRead;
-use crate::ram::RamReadWrite;
+use crate::cart::{CartContext, CartRead};
 use crate::cpu::CpuContext;
+use crate::dma::DmaContext;
+use crate::hdma::HdmaContext;
+use crate::io::IoRegisters;
+use crate::model::Model;
+use crate::ppu::{LcdMode, PpuContext};
+use crate::ram::{RamContext, RamReadWrite};
+use crate::serial::SerialContext;
+use crate::timer::TimerContext;
+use crate::vmem::oam_locked;
+
+// Everything the CPU core sees of the machine. `read`/`write` are CPU
+// accesses and may have side effects, `peek` is for debuggers and must not.
+// `tick` runs the rest of the machine for one M-cycle.
+//
+// IF (0xFF0F) and IE (0xFFFF) go through the same address space by
+// default, implementations backed by dedicated latches can override the
+// accessors.
+pub trait Bus {
+    fn read(&mut self, address: u16) -> u8;
+    fn write(&mut self, address: u16, value: u8);
+    fn peek(&self, address: u16) -> u8;
+
+    fn tick(&mut self) {}
+
+    // Frames the display has completed, for trace filtering.
+    fn frame(&self) -> u64 {
+        0
+    }
+
+    fn int_flags(&self) -> u8 {
+        self.peek(0xFF0F) & 0x1F
+    }
+
+    fn set_int_flags(&mut self, value: u8) {
+        self.write(0xFF0F, value & 0x1F);
+    }
+
+    fn int_enable(&self) -> u8 {
+        self.peek(0xFFFF)
+    }
+
+    // Something else owns the bus and the CPU has to wait.
+    fn stalled(&self) -> bool {
+        false
+    }
+
+    // Called by STOP, returns true if it switched the CPU speed instead.
+    fn speed_switch(&mut self) -> bool {
+        false
+    }
+
+    // Whether a button was pressed since the last call. STOP only wakes on
+    // a new press, a joypad request already pending in IF doesn't count.
+    fn joypad_edge(&mut self) -> bool {
+        false
+    }
+}
+
 // 0x0000 - 0x3FFF : ROM Bank 0
 // 0x4000 - 0x7FFF : ROM Bank 1 - Switchable
 // 0x8000 - 0x97FF : CHR RAM
@@ -9,23 +66,53 @@ use crate::cpu::CpuContext;
 // 0xA000 - 0xBFFF : Cartridge RAM
 // 0xC000 - 0xCFFF : RAM Bank 0
 // 0xD000 - 0xDFFF : RAM Bank 1-7 - switchable - Color only
-// 0xE000 - 0xFDFF : Reserved - Echo RAM
+// 0xE000 - 0xFDFF : Echo RAM - mirrors 0xC000 - 0xDDFF
 // 0xFE00 - 0xFE9F : Object Attribute Memory
 // 0xFEA0 - 0xFEFF : Reserved - Unusable
 // 0xFF00 - 0xFF7F : I/O Registers
-// 0xFF80 - 0xFFFE : Zero Pagezz
-impl<'a> CpuContext<'a> {
+// 0xFF80 - 0xFFFE : Zero Page
+pub struct BusContext<'a> {
+    pub model: Model,
+    pub cart: &'a mut CartContext,
+    pub ram: RamContext,
+    pub timer: TimerContext,
+    pub ppu: PpuContext,
+    pub serial: SerialContext,
+    pub dma: DmaContext,
+    pub hdma: HdmaContext,
+    pub io: IoRegisters,
+    pub ie_register: u8,
+    pub int_flags: u8,
+}
+
+impl<'a> BusContext<'a> {
+    pub fn new(cart: &'a mut CartContext, model: Model) -> Self {
+        BusContext {
+            model,
+            cart,
+            ram: RamContext::new(),
+            timer: TimerContext::new(),
+            ppu: PpuContext::new(),
+            serial: SerialContext::new(),
+            dma: DmaContext::new(),
+            hdma: HdmaContext::new(),
+            io: IoRegisters::new(),
+            ie_register: 0,
+            int_flags: 0,
+        }
+    }
+
     pub fn bus_read(&self, address: u16) -> u8 {
         match address {
             0x0000..=0x7FFF => self.cart.cart_read(address), // ROM Data
-            0x8000..=0x9FFF => panic!("UNSUPPORTED bus_read({:04X})", address), // Char/Map Data
+            0x8000..=0x9FFF => self.ppu.vmem.vram_read(address, self.ppu.mode), // Char/Map Data
             0xA000..=0xBFFF => self.cart.cart_read(address), // Cartridge RAM
             0xC000..=0xDFFF => self.ram.wram_read(address), // Working RAM
-            0xE000..=0xFDFF => 0, // Echo RAM
-            0xFE00..=0xFE9F => panic!("UNSUPPORTED bus_read({:04X})", address), // OAM
-            0xFEA0..=0xFEFF => 0, // Unusable
-            0xFF00..=0xFF7F => panic!("UNSUPPORTED bus_read({:04X})", address), // IO Registers
-            0xFFFF => self.cpu_get_ie_register(), // IE Register
+            0xE000..=0xFDFF => self.ram.wram_read(address - 0x2000), // Echo RAM
+            0xFE00..=0xFE9F => self.ppu.vmem.oam_read(address, self.ppu.mode), // OAM
+            0xFEA0..=0xFEFF => self.unusable_read(address), // Unusable
+            0xFF00..=0xFF7F => self.io_read(address), // IO Registers
+            0xFFFF => self.ie_register, // IE Register
             _ => self.ram.hram_read(address), // HRAM
         }
     }
@@ -33,26 +120,168 @@ impl<'a> CpuContext<'a> {
     pub fn bus_write(&mut self, address: u16, value: u8) {
         match address {
             0x0000..=0x7FFF => self.cart.cart_write(address, value),
-            0x8000..=0x9FFF => panic!("UNSUPPORTED bus_write({:04X})", address),
+            0x8000..=0x9FFF => self.ppu.vmem.vram_write(address, value, self.ppu.mode),
             0xA000..=0xBFFF => self.cart.cart_write(address, value),
             0xC000..=0xDFFF => self.ram.wram_write(address, value),
-            0xE000..=0xFDFF => todo!("reserved echo ram"),
-            0xFE00..=0xFE9F => panic!("UNSUPPORTED bus_write({:04X})", address),
-            0xFEA0..=0xFEFF => panic!("unusable reserved"),
-            0xFF00..=0xFF7F => panic!("UNSUPPORTED bus_write({:04X})", address),
-            0xFFFF => self.cpu_set_ie_register(value),
+            0xE000..=0xFDFF => self.ram.wram_write(address - 0x2000, value), // Echo RAM
+            0xFE00..=0xFE9F => self.ppu.vmem.oam_write(address, value, self.ppu.mode), // OAM
+            0xFEA0..=0xFEFF => (), // Unusable, writes are ignored
+            0xFF00..=0xFF7F => self.io_write(address, value),
+            0xFFFF => self.ie_register = value,
             _ => self.ram.hram_write(address, value),
         }
     }
 
-    pub fn bus_read16(&self, address: u16) -> u16 {
+    // Reads 0xFF while the PPU has OAM locked. Otherwise DMG returns 0 and
+    // CGB repeats the high nibble of the low address byte (0xFEA5 -> 0xAA).
+    fn unusable_read(&self, address: u16) -> u8 {
+        if oam_locked(self.ppu.mode) {
+            return 0xFF;
+        }
+
+        match self.model {
+            Model::Dmg => 0x00,
+            Model::Cgb => {
+                let nibble = (address as u8) & 0xF0;
+                nibble | (nibble >> 4)
+            }
+        }
+    }
+}
+
+impl<'a> Bus for BusContext<'a> {
+    // While OAM DMA runs the CPU only reaches 0xFF00 and up, everything
+    // else reads the byte being copied.
+    fn read(&mut self, address: u16) -> u8 {
+        if self.dma.dma_blocks(address) {
+            return self.dma.value;
+        }
+
+        self.bus_read(address)
+    }
+
+    fn write(&mut self, address: u16, value: u8) {
+        if self.dma.dma_blocks(address) {
+            return;
+        }
+
+        self.bus_write(address, value);
+    }
+
+    fn peek(&self, address: u16) -> u8 {
+        self.bus_read(address)
+    }
+
+    // One M-cycle is four dots, or two in double speed. The timer and the
+    // serial port run off the CPU clock and always get four T-cycles.
+    fn tick(&mut self) {
+        self.dma_tick();
+        self.hdma_tick();
+
+        // DIV is held while the speed changes.
+        let switching = self.io.speed_switch > 0;
+        self.io.speed_switch = self.io.speed_switch.saturating_sub(1);
+
+        let double_speed = self.double_speed();
+        let mode = self.ppu.mode;
+        for i in 0..4 {
+            if !switching {
+                self.int_flags |= self.timer.timer_tick();
+            }
+            // The frame sequencer stays at 512 Hz, one DIV bit higher.
+            let div = if double_speed { self.timer.div >> 1 } else { self.timer.div };
+            self.io.apu.apu_tick(div);
+            if !double_speed || i & 1 == 0 {
+                self.int_flags |= self.ppu.ppu_tick();
+            }
+            self.int_flags |= self.serial.serial_tick();
+        }
+
+        if mode != LcdMode::HBlank && self.ppu.mode == LcdMode::HBlank {
+            self.hdma.hdma_hblank();
+        }
+    }
+
+    fn frame(&self) -> u64 {
+        self.ppu.current_frame
+    }
+
+    fn int_flags(&self) -> u8 {
+        self.int_flags
+    }
+
+    fn set_int_flags(&mut self, value: u8) {
+        self.int_flags = value & 0x1F;
+    }
+
+    fn int_enable(&self) -> u8 {
+        self.ie_register
+    }
+
+    fn stalled(&self) -> bool {
+        self.hdma.hdma_stalled() || self.io.speed_switch > 0
+    }
+
+    fn speed_switch(&mut self) -> bool {
+        self.key1_switch()
+    }
+
+    fn joypad_edge(&mut self) -> bool {
+        self.io.joypad.joypad_take_edge()
+    }
+}
+
+// Plain 64 KiB of RAM with nothing mapped, for running the CPU core on
+// its own.
+pub struct FlatMemory {
+    pub mem: Box<[u8; 0x10000]>,
+}
+
+impl Default for FlatMemory {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl FlatMemory {
+    pub fn new() -> Self {
+        FlatMemory {
+            mem: Box::new([0; 0x10000]),
+        }
+    }
+}
+
+impl Bus for FlatMemory {
+    fn read(&mut self, address: u16) -> u8 {
+        self.mem[address as usize]
+    }
+
+    fn write(&mut self, address: u16, value: u8) {
+        self.mem[address as usize] = value;
+    }
+
+    fn peek(&self, address: u16) -> u8 {
+        self.mem[address as usize]
+    }
+}
+
+impl<B: Bus> CpuContext<B> {
+    pub fn bus_read(&mut self, address: u16) -> u8 {
+        self.bus.read(address)
+    }
+
+    pub fn bus_write(&mut self, address: u16, value: u8) {
+        self.bus.write(address, value);
+    }
+
+    pub fn bus_read16(&mut self, address: u16) -> u16 {
         let lo = self.bus_read(address) as u16;
-        let hi = self.bus_read(address + 1) as u16;
+        let hi = self.bus_read(address.wrapping_add(1)) as u16;
         lo | (hi << 8)
     }
 
     pub fn bus_write16(&mut self, address: u16, value: u16) {
-        self.bus_write(address + 1, ((value >> 8) & 0xFF) as u8);
+        self.bus_write(address.wrapping_add(1), ((value >> 8) & 0xFF) as u8);
         self.bus_write(address, (value & 0xFF) as u8);
     }
-}
\ No newline at end of file
+}
@@ -1,7 +1,9 @@
 use std::fs::File;
-use std::io::{self, Read};
+use std::io::Read;
 use std::mem::MaybeUninit;
 
+use crate::error::EmuError;
+
 #[derive(Debug)]
 struct RomHeader {
     entry: [u8; 4],
@@ -35,6 +37,14 @@ pub struct CartContext {
     rom_size: u32,
     rom_data: Vec<u8>,
     header: Option<RomHeader>,
+    // MBC1 state, unused on ROM only cartridges.
+    mbc1: bool,
+    ram_enabled: bool,
+    rom_bank: u8,
+    ram_bank: u8,
+    banking_mode: u8,
+    // External RAM at 0xA000 - 0xBFFF, sized by the header.
+    ram_data: Vec<u8>,
 }
 
 const ROM_TYPES: [&str; 35] = [
@@ -142,6 +152,12 @@ fn lic_code(code: u8) -> String {
     }
 }
 
+impl Default for CartContext {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 impl CartContext {
     pub fn new() -> Self {
         CartContext {
@@ -149,21 +165,32 @@ impl CartContext {
             rom_size: 0,
             rom_data: Vec::new(),
             header: None,
+            mbc1: false,
+            ram_enabled: false,
+            rom_bank: 1,
+            ram_bank: 0,
+            banking_mode: 0,
+            ram_data: Vec::new(),
         }
     }
 
-    pub fn cart_load(&mut self, cart: &str) -> io::Result<bool> {
+    pub fn cart_load(&mut self, cart: &str) -> Result<(), EmuError> {
         self.filename = cart.to_string();
 
-        let mut file = File::open(cart)?;
+        let load_err = |source| EmuError::CartLoad {
+            path: cart.to_string(),
+            source,
+        };
+
+        let mut file = File::open(cart).map_err(load_err)?;
         println!("Opened: {}", self.filename);
 
         // Get file size (ROM size)
-        self.rom_size = file.metadata()?.len() as u32;
+        self.rom_size = file.metadata().map_err(load_err)?.len() as u32;
 
         // Read the ROM data
         self.rom_data.clear();
-        file.read_to_end(&mut self.rom_data)?;
+        file.read_to_end(&mut self.rom_data).map_err(load_err)?;
 
         let header_offset = 0x100;
         if self.rom_data.len() > header_offset + std::mem::size_of::<RomHeader>() {
@@ -197,6 +224,8 @@ impl CartContext {
                 self.rom_data[header_offset + 0x4F],
             ]);
 
+            self.mbc1 = (0x01..=0x03).contains(&header.card_type);
+            self.ram_data = vec![0; ram_size(header.ram_size)];
             self.header = Some(header);
         }
 
@@ -236,7 +265,7 @@ impl CartContext {
             );
         }
 
-        Ok(true)
+        Ok(())
     }
 
     fn cart_lic_name(&self) -> String {
@@ -258,18 +287,82 @@ impl CartContext {
     }
 }
 
+fn ram_size(code: u8) -> usize {
+    match code {
+        0x01 => 0x800,
+        0x02 => 0x2000,
+        0x03 => 0x8000,
+        0x04 => 0x20000,
+        0x05 => 0x10000,
+        _ => 0,
+    }
+}
+
 pub trait CartRead {
     fn cart_read(&self, address: u16) -> u8;
     fn cart_write(&mut self, address: u16, value: u8);
 }
 
+impl CartContext {
+    // In mode 1 the upper bank bits also apply to bank 0 and cart RAM.
+    fn cart_rom_offset(&self, address: u16) -> usize {
+        let bank = match address {
+            0x0000..=0x3FFF if self.banking_mode == 1 => (self.ram_bank << 5) as usize,
+            0x0000..=0x3FFF => 0,
+            _ => ((self.ram_bank << 5) | self.rom_bank) as usize,
+        };
+        bank * 0x4000 + (address as usize & 0x3FFF)
+    }
+
+    fn cart_ram_offset(&self, address: u16) -> usize {
+        let bank = if self.banking_mode == 1 {
+            self.ram_bank as usize
+        } else {
+            0
+        };
+        bank * 0x2000 + (address as usize - 0xA000)
+    }
+
+    // MBC1 RAM has to be enabled first, a ROM only cartridge's is always
+    // there.
+    fn cart_ram_available(&self) -> bool {
+        !self.ram_data.is_empty() && (self.ram_enabled || !self.mbc1)
+    }
+}
 
 impl CartRead for CartContext {
+    // Past the end of the image (or with no cartridge RAM) the bus floats.
     fn cart_read(&self, address: u16) -> u8 {
-        self.rom_data[address as usize]
+        match address {
+            0xA000..=0xBFFF => {
+                if !self.cart_ram_available() {
+                    return 0xFF;
+                }
+                let offset = self.cart_ram_offset(address) % self.ram_data.len();
+                self.ram_data[offset]
+            }
+            _ if self.mbc1 => {
+                let offset = self.cart_rom_offset(address) % self.rom_data.len();
+                self.rom_data[offset]
+            }
+            _ => self.rom_data.get(address as usize).copied().unwrap_or(0xFF),
+        }
     }
-    
+
+    // A ROM-only cartridge has nothing to latch writes, they are dropped.
     fn cart_write(&mut self, address: u16, value: u8) {
-        panic!("Not implemented cart write!");
+        match address {
+            0xA000..=0xBFFF => {
+                if self.cart_ram_available() {
+                    let offset = self.cart_ram_offset(address) % self.ram_data.len();
+                    self.ram_data[offset] = value;
+                }
+            }
+            _ if !self.mbc1 => (),
+            0x0000..=0x1FFF => self.ram_enabled = value & 0x0F == 0x0A,
+            0x2000..=0x3FFF => self.rom_bank = (value & 0x1F).max(1),
+            0x4000..=0x5FFF => self.ram_bank = value & 0x03,
+            _ => self.banking_mode = value & 0x01,
+        }
     }
 }
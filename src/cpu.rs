@@ -1,9 +1,64 @@
-use crate::cart::CartContext;
-use crate::instructions::{self, inst_name, InType};
-use crate::ram::RamContext;
+use crate::bus::Bus;
+use crate::error::EmuError;
+use crate::instructions::{instruction_by_opcode, InType, Instruction};
+use crate::model::Model;
+use crate::trace::{TraceCompareContext, TraceContext};
+
+const FLAG_Z: u8 = 1 << 7;
+const FLAG_N: u8 = 1 << 6;
+const FLAG_H: u8 = 1 << 5;
+const FLAG_C: u8 = 1 << 4;
+
+// Typed view of the F register.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct Flags {
+    pub z: bool,
+    pub n: bool,
+    pub h: bool,
+    pub c: bool,
+}
+
+impl Flags {
+    pub const fn new(z: bool, n: bool, h: bool, c: bool) -> Self {
+        Flags { z, n, h, c }
+    }
+}
+
+impl From<u8> for Flags {
+    fn from(f: u8) -> Self {
+        Flags {
+            z: f & FLAG_Z != 0,
+            n: f & FLAG_N != 0,
+            h: f & FLAG_H != 0,
+            c: f & FLAG_C != 0,
+        }
+    }
+}
+
+impl From<Flags> for u8 {
+    fn from(flags: Flags) -> Self {
+        let mut f = 0;
+        if flags.z {
+            f |= FLAG_Z;
+        }
+        if flags.n {
+            f |= FLAG_N;
+        }
+        if flags.h {
+            f |= FLAG_H;
+        }
+        if flags.c {
+            f |= FLAG_C;
+        }
+        f
+    }
+}
+
+// F is private so that its low nibble, which doesn't exist in hardware,
+// always reads back as zero.
 pub struct CpuRegister {
     pub a: u8,
-    pub f: u8,
+    f: u8,
     pub b: u8,
     pub c: u8,
     pub d: u8,
@@ -14,6 +69,12 @@ pub struct CpuRegister {
     pub sp: u16,
 }
 
+impl Default for CpuRegister {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 impl CpuRegister {
     pub fn new() -> Self {
         CpuRegister {
@@ -29,102 +90,274 @@ impl CpuRegister {
             sp: 0,
         }
     }
+
+    pub fn f(&self) -> u8 {
+        self.f
+    }
+
+    pub fn set_f(&mut self, value: u8) {
+        self.f = value & 0xF0;
+    }
+
+    pub fn flags(&self) -> Flags {
+        Flags::from(self.f)
+    }
+
+    pub fn set_flags(&mut self, flags: Flags) {
+        self.f = flags.into();
+    }
+
+    pub fn af(&self) -> u16 {
+        u16::from_be_bytes([self.a, self.f])
+    }
+
+    pub fn set_af(&mut self, value: u16) {
+        let [hi, lo] = value.to_be_bytes();
+        self.a = hi;
+        self.set_f(lo);
+    }
+
+    pub fn bc(&self) -> u16 {
+        u16::from_be_bytes([self.b, self.c])
+    }
+
+    pub fn set_bc(&mut self, value: u16) {
+        [self.b, self.c] = value.to_be_bytes();
+    }
+
+    pub fn de(&self) -> u16 {
+        u16::from_be_bytes([self.d, self.e])
+    }
+
+    pub fn set_de(&mut self, value: u16) {
+        [self.d, self.e] = value.to_be_bytes();
+    }
+
+    pub fn hl(&self) -> u16 {
+        u16::from_be_bytes([self.h, self.l])
+    }
+
+    pub fn set_hl(&mut self, value: u16) {
+        [self.h, self.l] = value.to_be_bytes();
+    }
 }
 
-pub struct CpuContext<'a> {
+pub struct CpuContext<B: Bus> {
     pub regs: CpuRegister,
     pub fetched_data: u16,
     pub mem_dest: u16,
     pub cur_opcode: u8,
-    pub cur_inst: Option<instructions::Instruction>,
-    pub ram: RamContext,
-    pub cart: &'a mut CartContext,
+    pub cur_inst: &'static Instruction,
+    pub bus: B,
     pub dest_is_mem: bool,
     pub halted: bool,
+    pub halt_bug: bool,
+    pub stopped: bool,
+    // Set by an illegal opcode, only a reset gets the CPU going again.
+    pub locked: bool,
     pub int_master_enable: bool,
-    pub ie_register: u8,
+    pub enabling_ime: bool,
     pub stepping: bool,
+    // Instruction trace, off unless a TraceContext is installed.
+    pub tracer: Option<TraceContext>,
+    // Reference log every instruction is checked against.
+    pub trace_compare: Option<TraceCompareContext>,
+    // Machine clock in M-cycles, and the M-cycles taken by the current step.
+    pub ticks: u64,
+    pub step_cycles: u8,
+    // First error raised by the CPU itself during the current step.
+    pub(crate) fault: Option<EmuError>,
 }
 
-impl<'a> CpuContext<'a> {
-    pub fn new(cart: &'a mut CartContext) -> Self {
+impl<B: Bus> CpuContext<B> {
+    pub fn new(bus: B) -> Self {
         CpuContext {
             regs: CpuRegister::new(),
             fetched_data: 0,
             mem_dest: 0,
             cur_opcode: 0,
-            cur_inst: None,
-            int_master_enable: true,
-            ram: RamContext::new(),
-            cart,
+            cur_inst: instruction_by_opcode(0x00),
+            int_master_enable: false,
+            enabling_ime: false,
+            bus,
             dest_is_mem: false,
-            ie_register: 0,
             halted: false,
+            halt_bug: false,
+            stopped: false,
+            locked: false,
             stepping: false,
+            tracer: None,
+            trace_compare: None,
+            ticks: 0,
+            step_cycles: 0,
+            fault: None,
         }
     }
 
+    // Register state the boot ROM leaves behind when it jumps to the
+    // cartridge entry point. Games tell the models apart by A.
+    pub fn cpu_init(&mut self, model: Model) {
+        match model {
+            Model::Dmg => {
+                self.regs.set_af(0x01B0);
+                self.regs.set_bc(0x0013);
+                self.regs.set_de(0x00D8);
+                self.regs.set_hl(0x014D);
+            }
+            Model::Cgb => {
+                self.regs.set_af(0x1180);
+                self.regs.set_bc(0x0000);
+                self.regs.set_de(0xFF56);
+                self.regs.set_hl(0x000D);
+            }
+        }
+        self.regs.sp = 0xFFFE;
+        self.regs.pc = 0x0100;
+    }
+
     fn fetch_instruction(&mut self) {
         self.cur_opcode = self.bus_read(self.regs.pc);
-        self.regs.pc = self.regs.pc.wrapping_add(1);
-        self.cur_inst = instructions::instruction_by_opcode(self.cur_opcode);
+
+        // After the HALT bug the byte following HALT is read twice.
+        if self.halt_bug {
+            self.halt_bug = false;
+        } else {
+            self.regs.pc = self.regs.pc.wrapping_add(1);
+        }
+        self.emu_cycle(1);
+        self.cur_inst = instruction_by_opcode(self.cur_opcode);
     }
 
     fn execute(&mut self) {
-        if let Some(inst) = &self.cur_inst {
-            match inst.type_in {
-                InType::InNone => self.proc_none(),
-                InType::InLd => self.proc_ld(),
-                InType::InLdh => self.proc_ldh(),
-                InType::InJp => self.proc_jp(),
-                InType::InDi => self.proc_di(),
-                InType::InXor => self.proc_xor(),
-                InType::InPop => self.proc_pop(),
-                InType::InPush => self.proc_push(),
-                InType::InJr => self.proc_jr(),
-                InType::InCall => self.proc_call(),
-                InType::InRet => self.proc_ret(),
-                InType::InRst => self.proc_rst(),
-                InType::InReti => self.proc_reti(),
-                InType::InNop => (),
-                _ => (),
-            }
+        match self.cur_inst.type_in {
+            InType::InNone => self.proc_none(),
+            InType::InLd => self.proc_ld(),
+            InType::InLdh => self.proc_ldh(),
+            InType::InJp => self.proc_jp(),
+            InType::InDi => self.proc_di(),
+            InType::InEi => self.proc_ei(),
+            InType::InHalt => self.proc_halt(),
+            InType::InStop => self.proc_stop(),
+            InType::InXor => self.proc_xor(),
+            InType::InInc => self.proc_inc(),
+            InType::InDec => self.proc_dec(),
+            InType::InAdd => self.proc_add(),
+            InType::InAdc => self.proc_adc(),
+            InType::InSub => self.proc_sub(),
+            InType::InSbc => self.proc_sbc(),
+            InType::InAnd => self.proc_and(),
+            InType::InOr => self.proc_or(),
+            InType::InCp => self.proc_cp(),
+            InType::InCb => self.proc_cb(),
+            InType::InRlca => self.proc_rlca(),
+            InType::InRrca => self.proc_rrca(),
+            InType::InRla => self.proc_rla(),
+            InType::InRra => self.proc_rra(),
+            InType::InDaa => self.proc_daa(),
+            InType::InCpl => self.proc_cpl(),
+            InType::InScf => self.proc_scf(),
+            InType::InCcf => self.proc_ccf(),
+            InType::InPop => self.proc_pop(),
+            InType::InPush => self.proc_push(),
+            InType::InJr => self.proc_jr(),
+            InType::InCall => self.proc_call(),
+            InType::InRet => self.proc_ret(),
+            InType::InRst => self.proc_rst(),
+            InType::InReti => self.proc_reti(),
+            InType::InNop => (),
+            _ => (),
         }
     }
 
-    pub fn cpu_step(&mut self) -> bool {
+    // Executes one instruction (or one M-cycle of HALT), servicing any
+    // interrupt, and returns the number of M-cycles consumed. Faults raised
+    // during the step are reported once it completes.
+    pub fn cpu_step(&mut self) -> Result<u8, EmuError> {
+        self.step_cycles = 0;
+        self.fault = None;
+
+        // Nothing wakes a locked up CPU, but the clock keeps running.
+        if self.locked {
+            self.emu_cycle(1);
+            return Ok(self.step_cycles);
+        }
+
+        // VRAM DMA or a speed switch has the bus, the CPU waits it out.
+        if self.bus.stalled() {
+            self.emu_cycle(1);
+            return Ok(self.step_cycles);
+        }
+
+        // The system clock is stopped, nothing runs until a button press.
+        if self.stopped {
+            if self.bus.joypad_edge() {
+                self.stopped = false;
+            }
+            return Ok(0);
+        }
+
         if !self.halted {
+            if let Some(tracer) = &mut self.tracer {
+                if let Err(err) = tracer.trace(&self.regs, &self.bus, self.ticks) {
+                    self.cpu_fault(EmuError::TraceIo(err));
+                }
+            }
+
+            // Stop before running the first instruction that diverges.
+            if let Some(compare) = &mut self.trace_compare {
+                match compare.compare(&self.regs, &self.bus) {
+                    Ok(None) => (),
+                    Ok(Some(mismatch)) => return Err(EmuError::TraceMismatch(Box::new(mismatch))),
+                    Err(err) => {
+                        self.cpu_fault(EmuError::TraceIo(err));
+                    }
+                }
+            }
+
             self.fetch_instruction();
             self.fetch_data();
-            if let Some(inst) = &self.cur_inst {
-                println!(
-                    "PC: {:04X}  INST: {}  ({:02X} {:02X} {:02X}) A: {:02X} BC: {:02X}{:02X} DE: {:02X}{:02X} HL: {:02X}{:02X}",
-                    self.regs.pc,
-                    inst_name(&inst.type_in),
-                    self.cur_opcode,
-                    self.bus_read(self.regs.pc + 1),
-                    self.bus_read(self.regs.pc + 2),
-                    self.regs.a,
-                    self.regs.b,
-                    self.regs.c,
-                    self.regs.d,
-                    self.regs.e,
-                    self.regs.h,
-                    self.regs.l,
-                );
-            }
             self.execute();
+        } else {
+            self.emu_cycle(1);
+
+            if self.cpu_pending_interrupts() != 0 {
+                self.halted = false;
+            }
+        }
+
+        if self.int_master_enable {
+            self.cpu_handle_interrupts();
+            self.enabling_ime = false;
+        }
+
+        // EI only takes effect after the instruction following it.
+        if self.enabling_ime {
+            self.enabling_ime = false;
+            self.int_master_enable = true;
+        }
+
+        match self.fault.take() {
+            Some(err) => Err(err),
+            None => Ok(self.step_cycles),
         }
-        true
     }
 
-    pub fn cpu_get_ie_register(&self) -> u8 {
-        self.ie_register
+    // Runs the rest of the machine in lockstep with the CPU, one M-cycle
+    // (four dots) at a time.
+    pub fn emu_cycle(&mut self, cpu_cycles: u8) {
+        for _ in 0..cpu_cycles {
+            self.bus.tick();
+            self.ticks += 1;
+            self.step_cycles += 1;
+        }
     }
 
-    pub fn cpu_set_ie_register(&mut self, n: u8) {
-        self.ie_register = n;
+    // Keeps the first fault of the step for cpu_step to report, and returns
+    // the open bus value for reads.
+    pub fn cpu_fault(&mut self, err: EmuError) -> u8 {
+        if self.fault.is_none() {
+            self.fault = Some(err);
+        }
+        0xFF
     }
 }
-
-pub fn emu_cycle(cpu_cycles: u8) {}
@@ -1,129 +1,109 @@
-use crate::cpu::{CpuContext, emu_cycle};
+use crate::bus::Bus;
+use crate::cpu::CpuContext;
 use crate::instructions::{AddrMode, RegType};
 
-impl<'a> CpuContext<'a> {
+impl<B: Bus> CpuContext<B> {
+    // Reads the next immediate byte, one M-cycle.
+    fn fetch_d8(&mut self) -> u8 {
+        let val = self.bus_read(self.regs.pc);
+        self.emu_cycle(1);
+        self.regs.pc = self.regs.pc.wrapping_add(1);
+        val
+    }
+
+    // Reads the next little-endian immediate word, two M-cycles.
+    fn fetch_d16(&mut self) -> u16 {
+        let lo = self.fetch_d8() as u16;
+        let hi = self.fetch_d8() as u16;
+        lo | (hi << 8)
+    }
+
+    // Reads memory for an operand, one M-cycle.
+    fn fetch_mem(&mut self, addr: u16) -> u16 {
+        let val = self.bus_read(addr) as u16;
+        self.emu_cycle(1);
+        val
+    }
+
+    // (C) addresses the high page, every other register is a full pointer.
+    fn reg_addr(&self, rt: &RegType) -> u16 {
+        match rt {
+            RegType::RtC => 0xFF00 | self.regs.c as u16,
+            _ => self.cpu_read_reg(rt),
+        }
+    }
+
     pub fn fetch_data(&mut self) {
         self.mem_dest = 0;
         self.dest_is_mem = false;
 
-        if let Some(inst) = &self.cur_inst {
-            match inst.mode {
-                AddrMode::AmImp => return,
-                AddrMode::AmR => self.fetched_data = self.cpu_read_reg(&inst.reg_1),
-                AddrMode::AmRr => self.fetched_data = self.cpu_read_reg(&inst.reg_2),
-                AddrMode::AmRD8 => {
-                    self.fetched_data = self.bus_read(self.regs.pc) as u16;
-                    emu_cycle(1);
-                    self.regs.pc += 1;
-                }
-                AddrMode::AmRD16 => (),
-                AddrMode::AmD16 => {
-                    let lo = self.bus_read(self.regs.pc) as u16;
-                    emu_cycle(1);
-                    let hi = self.bus_read(self.regs.pc.wrapping_add(1)) as u16;
-                    emu_cycle(1);
-                    self.fetched_data = lo | (hi << 8);
-                    self.regs.pc += 2;
-                }
-                AddrMode::AmMrr => {
-                    self.fetched_data = self.cpu_read_reg(&inst.reg_2);
-                    self.mem_dest = self.cpu_read_reg(&inst.reg_1);
-                    self.dest_is_mem = true;
+        let inst = self.cur_inst;
 
-                    match inst.reg_1 {
-                        RegType::RtC => self.mem_dest |= 0xFF00,
-                        _ => (),
-                    }
-                }
-                AddrMode::AmRMr => {
-                    let mut addr: u16 = self.cpu_read_reg(&inst.reg_2);
-
-                    match inst.reg_1 {
-                        RegType::RtC => addr |= 0xFF00,
-                        _ => (),
-                    }
-
-                    self.fetched_data = self.bus_read(addr) as u16;
-                }
-                AddrMode::AmRhli => {
-                    self.fetched_data =
-                        self.bus_read(self.cpu_read_reg(&inst.reg_2)) as u16;
-                    emu_cycle(1);
-                    self.cpu_set_reg(&RegType::RtHl, self.cpu_read_reg(&RegType::RtHl).wrapping_add(1));
-                }
-                AddrMode::AmRhld => {
-                    self.fetched_data = self.bus_read(self.cpu_read_reg(&inst.reg_2)) as u16;
-                    emu_cycle(1);
-                    self.cpu_set_reg(&RegType::RtHl, self.cpu_read_reg(&RegType::RtHl).wrapping_sub(1));
-                }
-                AddrMode::AmHlir => {
-                    self.fetched_data = self.cpu_read_reg(&inst.reg_2);
-                    self.mem_dest = self.cpu_read_reg(&inst.reg_1);
-                    self.dest_is_mem = true;
-                    self.cpu_set_reg(&RegType::RtHl, self.cpu_read_reg(&RegType::RtHl).wrapping_add(1));
-                },
-                AddrMode::AmHldr => {
-                    self.fetched_data = self.cpu_read_reg(&inst.reg_2);
-                    self.mem_dest = self.cpu_read_reg(&inst.reg_1);
-                    self.dest_is_mem = true;
-                    self.cpu_set_reg(&RegType::RtHl, self.cpu_read_reg(&RegType::RtHl).wrapping_sub(1));
-                },
-                AddrMode::AmRa8 => {
-                    self.fetched_data = self.bus_read(self.regs.pc) as u16;
-                    emu_cycle(1);
-                    self.regs.pc += 1;
-                },
-                AddrMode::AmA8r => {
-                    self.mem_dest = self.bus_read(self.regs.pc) as u16 | 0xFF00;
-                    self.dest_is_mem = true;
-                    emu_cycle(1);
-                    self.regs.pc += 1;
-                },
-                AddrMode::AmHlspr => {
-                    self.fetched_data = self.bus_read(self.regs.pc) as u16;
-                    emu_cycle(1);
-                    self.regs.pc += 1;
-                },
-                AddrMode::AmD8 => {
-                    self.fetched_data = self.bus_read(self.regs.pc) as u16;
-                    emu_cycle(1);
-                    self.regs.pc += 1;
-                },
-                AddrMode::AmA16r => {},  
-                AddrMode::AmD16r => {
-                    let lo = self.bus_read(self.regs.pc);
-                    emu_cycle(1);
-                    let hi = self.bus_read(self.regs.pc.wrapping_add(1));
-                    emu_cycle(1);
-                    self.mem_dest = (lo as u16) | ((hi as u16) << 8);
-                    self.dest_is_mem = true;
-                    self.regs.pc += 2;
-                    self.fetched_data = self.cpu_read_reg(&inst.reg_2);
-                },
-                AddrMode::AmMrd8 => {
-                    self.fetched_data = self.bus_read(self.regs.pc) as u16;
-                    emu_cycle(1);
-                    self.regs.pc += 1;
-                    self.mem_dest = self.cpu_read_reg(&inst.reg_1);
-                    self.dest_is_mem = true;
-                },
-                AddrMode::AmMr => {
-                    self.mem_dest = self.cpu_read_reg(&inst.reg_1);
-                    self.dest_is_mem = true;
-                    self.fetched_data = self.bus_read(self.cpu_read_reg(&inst.reg_1)) as u16;
-                    emu_cycle(1);
-                },
-                AddrMode::AmRa16 => {
-                    let lo = self.bus_read(self.regs.pc);
-                    emu_cycle(1);
-                    let hi = self.bus_read(self.regs.pc.wrapping_add(1));
-                    emu_cycle(1);
-                    let addr = (lo as u16) | ((hi as u16) << 8);
-                    self.regs.pc += 2;
-                    self.fetched_data = self.bus_read(addr) as u16;
-                    emu_cycle(1);
-                }
+        match inst.mode {
+            AddrMode::AmImp => (),
+            AddrMode::AmR => self.fetched_data = self.cpu_read_reg(&inst.reg_1),
+            AddrMode::AmRr => self.fetched_data = self.cpu_read_reg(&inst.reg_2),
+            AddrMode::AmRD8 | AddrMode::AmD8 | AddrMode::AmHlspr => {
+                self.fetched_data = self.fetch_d8() as u16;
+            }
+            AddrMode::AmRD16 | AddrMode::AmD16 => self.fetched_data = self.fetch_d16(),
+            AddrMode::AmMrr => {
+                self.fetched_data = self.cpu_read_reg(&inst.reg_2);
+                self.mem_dest = self.reg_addr(&inst.reg_1);
+                self.dest_is_mem = true;
+            }
+            AddrMode::AmRMr => {
+                let addr = self.reg_addr(&inst.reg_2);
+                self.fetched_data = self.fetch_mem(addr);
+            }
+            AddrMode::AmRhli => {
+                self.fetched_data = self.fetch_mem(self.regs.hl());
+                self.regs.set_hl(self.regs.hl().wrapping_add(1));
+            }
+            AddrMode::AmRhld => {
+                self.fetched_data = self.fetch_mem(self.regs.hl());
+                self.regs.set_hl(self.regs.hl().wrapping_sub(1));
+            }
+            AddrMode::AmHlir => {
+                self.fetched_data = self.cpu_read_reg(&inst.reg_2);
+                self.mem_dest = self.regs.hl();
+                self.dest_is_mem = true;
+                self.regs.set_hl(self.regs.hl().wrapping_add(1));
+            }
+            AddrMode::AmHldr => {
+                self.fetched_data = self.cpu_read_reg(&inst.reg_2);
+                self.mem_dest = self.regs.hl();
+                self.dest_is_mem = true;
+                self.regs.set_hl(self.regs.hl().wrapping_sub(1));
+            }
+            AddrMode::AmRa8 => {
+                let addr = 0xFF00 | self.fetch_d8() as u16;
+                self.fetched_data = self.fetch_mem(addr);
+            }
+            AddrMode::AmA8r => {
+                self.mem_dest = 0xFF00 | self.fetch_d8() as u16;
+                self.dest_is_mem = true;
+                self.fetched_data = self.cpu_read_reg(&inst.reg_2);
+            }
+            AddrMode::AmA16r | AddrMode::AmD16r => {
+                self.mem_dest = self.fetch_d16();
+                self.dest_is_mem = true;
+                self.fetched_data = self.cpu_read_reg(&inst.reg_2);
+            }
+            AddrMode::AmMrd8 => {
+                self.fetched_data = self.fetch_d8() as u16;
+                self.mem_dest = self.cpu_read_reg(&inst.reg_1);
+                self.dest_is_mem = true;
+            }
+            AddrMode::AmMr => {
+                self.mem_dest = self.cpu_read_reg(&inst.reg_1);
+                self.dest_is_mem = true;
+                self.fetched_data = self.fetch_mem(self.mem_dest);
+            }
+            AddrMode::AmRa16 => {
+                let addr = self.fetch_d16();
+                self.fetched_data = self.fetch_mem(addr);
             }
         }
     }
-}
\ No newline at end of file
+}
@@ -1,35 +1,53 @@
-use crate::common::{bit, bit_set};
-use crate::cpu::{emu_cycle, CpuContext};
-use crate::instructions::{AddrMode, CondType, RegType};
-
-impl<'a> CpuContext<'a> {
-    pub fn cpu_set_flags(&mut self, z: u8, n: u8, h: u8, c: u8) {
-        if z as i8 != -1 {
-            self.regs.f = bit_set!(self.regs.f, 7, z);
-        }
-        if n as i8 != -1 {
-            self.regs.f = bit_set!(self.regs.f, 6, n);
-        }
-        if h as i8 != -1 {
-            self.regs.f = bit_set!(self.regs.f, 5, h);
-        }
-        if c as i8 != -1 {
-            self.regs.f = bit_set!(self.regs.f, 4, c);
-        }
-    }
+use crate::bus::Bus;
+use crate::cpu::{CpuContext, Flags};
+use crate::error::EmuError;
+use crate::instructions::{cb_instruction_by_opcode, AddrMode, CondType, InType, RegType};
 
+impl<B: Bus> CpuContext<B> {
     pub fn proc_di(&mut self) {
         self.int_master_enable = false;
     }
 
-    // Instruction processing methods
-    pub fn proc_none(&self) {
-        panic!("INVALID INSTRUCTION");
+    pub fn proc_ei(&mut self) {
+        self.enabling_ime = true;
+    }
+
+    // With IME clear and an interrupt already pending HALT doesn't halt at
+    // all, and the CPU fails to increment PC on the next opcode fetch.
+    pub fn proc_halt(&mut self) {
+        if !self.int_master_enable && self.cpu_pending_interrupts() != 0 {
+            self.halt_bug = true;
+        } else {
+            self.halted = true;
+        }
+    }
+
+    // STOP is encoded as 10 00, the padding byte is consumed by fetch_data.
+    // It resets DIV by writing 0xFF04, then the CPU and peripherals sleep
+    // until a button is pressed, whatever IF already holds. On CGB with
+    // a speed switch armed in KEY1 it switches speed and carries on instead.
+    pub fn proc_stop(&mut self) {
+        self.bus_write(0xFF04, 0);
+        if !self.bus.speed_switch() {
+            // Forget presses from before STOP.
+            self.bus.joypad_edge();
+            self.stopped = true;
+        }
+    }
+
+    // The unused opcodes hang the CPU until reset, the rest of the machine
+    // keeps running.
+    pub fn proc_none(&mut self) {
+        self.locked = true;
+        self.cpu_fault(EmuError::IllegalOpcode {
+            opcode: self.cur_opcode,
+            pc: self.regs.pc.wrapping_sub(1),
+        });
     }
 
     pub fn proc_xor(&mut self) {
-        self.regs.a ^= self.fetched_data as u8 & 0xFF;
-        self.cpu_set_flags(if self.regs.a == 0 { 1 } else { 0 }, 0, 0, 0);
+        self.regs.a ^= self.fetched_data as u8;
+        self.regs.set_flags(Flags::new(self.regs.a == 0, false, false, false));
     }
 
     fn is_16_bit(&self, rt: &RegType) -> bool {
@@ -37,85 +55,66 @@ impl<'a> CpuContext<'a> {
     }
 
     pub fn proc_ld(&mut self) {
+        let inst = self.cur_inst;
+
         // Handle memory destination case first
         if self.dest_is_mem {
-            // Extract needed values before any mutable operations
-            let (is_16_bit, fetched_data, mem_dest) = if let Some(inst) = &self.cur_inst {
-                (
-                    self.is_16_bit(&inst.reg_2),
-                    self.fetched_data,
-                    self.mem_dest,
-                )
+            // LD (a16),SP stores the low byte first, one byte per M-cycle.
+            if self.is_16_bit(&inst.reg_2) {
+                self.bus_write(self.mem_dest, self.fetched_data as u8);
+                self.emu_cycle(1);
+                self.bus_write(self.mem_dest.wrapping_add(1), (self.fetched_data >> 8) as u8);
             } else {
-                return;
-            };
-
-            if is_16_bit {
-                emu_cycle(1);
-                self.bus_write16(mem_dest, fetched_data);
-            } else {
-                self.bus_write(mem_dest, fetched_data as u8);
+                self.bus_write(self.mem_dest, self.fetched_data as u8);
             }
-            emu_cycle(1);
+            self.emu_cycle(1);
             return;
         }
 
-        // Extract all needed values before any mutations
-        let (reg1, reg2, mode, fetched_data) = if let Some(inst) = &self.cur_inst {
-            (
-                inst.reg_1.clone(),
-                inst.reg_2.clone(),
-                inst.mode.clone(),
-                self.fetched_data,
-            )
-        } else {
-            return;
-        };
-
-        match mode {
+        match inst.mode {
             AddrMode::AmHlspr => {
-                let reg2_val = self.cpu_read_reg(&reg2);
-                let hflag =
-                    (reg2_val & 0xF) as u8 + if (fetched_data & 0xF) >= 0x10 { 1 } else { 0 };
-                let cflag =
-                    (reg2_val & 0xFF) as u8 + if (fetched_data & 0xFF) >= 0x100 { 1 } else { 0 };
-
-                self.cpu_set_flags(0, 0, hflag, cflag);
-                self.cpu_set_reg(&reg1, reg2_val + fetched_data);
+                let val = self.sp_plus_e8(self.fetched_data as u8);
+                self.emu_cycle(1);
+                self.cpu_set_reg(&inst.reg_1, val);
+            }
+            // LD SP,HL spends an extra cycle moving the 16-bit value.
+            AddrMode::AmRr if self.is_16_bit(&inst.reg_1) => {
+                self.emu_cycle(1);
+                self.cpu_set_reg(&inst.reg_1, self.fetched_data);
             }
-            _ => self.cpu_set_reg(&reg1, fetched_data),
+            _ => self.cpu_set_reg(&inst.reg_1, self.fetched_data),
         }
     }
 
+    // fetch_data has already resolved the 0xFF00 page for both directions.
     pub fn proc_ldh(&mut self) {
-        let (reg1, fetched_data) = if let Some(inst) = &self.cur_inst {
-            (
-                inst.reg_1.clone(),
-                self.fetched_data,
-            )
+        if self.dest_is_mem {
+            self.bus_write(self.mem_dest, self.fetched_data as u8);
+            self.emu_cycle(1);
         } else {
-            return;
-        };
-
-        match reg1 {
-            RegType::RtA => self.cpu_set_reg(&reg1, self.bus_read16(0xFF00 | fetched_data)),
-            _ => self.bus_write(0xFF00 | fetched_data, self.regs.a),
+            self.cpu_set_reg(&self.cur_inst.reg_1, self.fetched_data);
         }
     }
 
     fn goto_addr(&mut self, addr: u16, pushpc: bool) {
         if self.check_condition() {
             if pushpc {
-                emu_cycle(2);
+                self.emu_cycle(2);
                 self.stack_push16(self.regs.pc);
             }
 
             self.regs.pc = addr;
-            emu_cycle(1);
+            self.emu_cycle(1);
         }
     }
 
     pub fn proc_jp(&mut self) {
+        // JP HL loads PC straight from the register, no extra cycle.
+        if self.cur_inst.mode == AddrMode::AmR {
+            self.regs.pc = self.fetched_data;
+            return;
+        }
+
         self.goto_addr(self.fetched_data, false);
     }
 
@@ -124,103 +123,351 @@ impl<'a> CpuContext<'a> {
     }
 
     pub fn proc_jr(&mut self) {
-        let rel = (self.fetched_data & 0xFF) as i16;
-        let addr: i16 = self.regs.pc as i16 + rel;
-        self.goto_addr(addr as u16, false);
+        let rel = self.fetched_data as u8 as i8;
+        let addr = self.regs.pc.wrapping_add(rel as u16);
+        self.goto_addr(addr, false);
     }
 
     pub fn proc_pop(&mut self) {
         let lo: u16 = self.stack_pop() as u16;
-        emu_cycle(1);
+        self.emu_cycle(1);
         let hi: u16 = self.stack_pop() as u16;
-        emu_cycle(1);
+        self.emu_cycle(1);
+
+        // POP AF drops the low nibble of F, see CpuRegister::set_f.
+        self.cpu_set_reg(&self.cur_inst.reg_1, (hi << 8) | lo);
+    }
 
-        let n: u16 = (hi << 8) | lo;
+    pub fn proc_push(&mut self) {
+        let reg_value = self.cpu_read_reg(&self.cur_inst.reg_1);
+        let hi: u16 = (reg_value >> 8) & 0xFF;
+        self.emu_cycle(1);
+        self.stack_push(hi as u8);
+
+        let lo: u16 = reg_value & 0xFF;
+        self.emu_cycle(1);
+        self.stack_push(lo as u8);
 
-        let (reg1, _) = if let Some(inst) = &self.cur_inst {
-            (
-                inst.reg_1.clone(),
-                0,
-            )
+        self.emu_cycle(1);
+    }
+
+    pub fn proc_inc(&mut self) {
+        let reg1 = self.cur_inst.reg_1;
+
+        // 16-bit INC rr does not touch the flags.
+        if self.is_16_bit(&reg1) && !self.dest_is_mem {
+            self.emu_cycle(1);
+            let val = self.cpu_read_reg(&reg1).wrapping_add(1);
+            self.cpu_set_reg(&reg1, val);
+            return;
+        }
+
+        let old = self.fetched_data as u8;
+        let val = old.wrapping_add(1);
+
+        if self.dest_is_mem {
+            self.bus_write(self.mem_dest, val);
+            self.emu_cycle(1);
         } else {
+            self.cpu_set_reg(&reg1, val as u16);
+        }
+
+        self.regs.set_flags(Flags {
+            z: val == 0,
+            n: false,
+            h: old & 0x0F == 0x0F,
+            ..self.regs.flags()
+        });
+    }
+
+    pub fn proc_dec(&mut self) {
+        let reg1 = self.cur_inst.reg_1;
+
+        // 16-bit DEC rr does not touch the flags.
+        if self.is_16_bit(&reg1) && !self.dest_is_mem {
+            self.emu_cycle(1);
+            let val = self.cpu_read_reg(&reg1).wrapping_sub(1);
+            self.cpu_set_reg(&reg1, val);
             return;
-        };
+        }
+
+        let old = self.fetched_data as u8;
+        let val = old.wrapping_sub(1);
+
+        if self.dest_is_mem {
+            self.bus_write(self.mem_dest, val);
+            self.emu_cycle(1);
+        } else {
+            self.cpu_set_reg(&reg1, val as u16);
+        }
+
+        self.regs.set_flags(Flags {
+            z: val == 0,
+            n: true,
+            h: old & 0x0F == 0,
+            ..self.regs.flags()
+        });
+    }
+
+    pub fn proc_add(&mut self) {
+        let reg1 = self.cur_inst.reg_1;
 
         match reg1 {
-            RegType::RtAf => self.cpu_set_reg(&reg1, n & 0xFFF0),
-            _ => self.cpu_set_reg(&reg1, n),
+            RegType::RtHl => self.add_hl(self.fetched_data),
+            RegType::RtSp => {
+                let val = self.sp_plus_e8(self.fetched_data as u8);
+                self.emu_cycle(2);
+                self.regs.sp = val;
+            }
+            _ => self.alu_add(self.fetched_data as u8, 0),
         }
     }
 
-    pub fn proc_push(&mut self) {
-        if let Some(inst) = &self.cur_inst {
-            let reg_value = self.cpu_read_reg(&inst.reg_1);
-            let hi: u16 = (reg_value >> 8) & 0xFF;
-            emu_cycle(1);
-            self.stack_push(hi as u8);
+    // ADD HL,rr: Z is preserved, H and C come from bits 11 and 15.
+    fn add_hl(&mut self, val: u16) {
+        let hl = self.cpu_read_reg(&RegType::RtHl);
+        let res = hl as u32 + val as u32;
+        let h = (hl & 0x0FFF) + (val & 0x0FFF) > 0x0FFF;
+
+        self.emu_cycle(1);
+        self.cpu_set_reg(&RegType::RtHl, res as u16);
+        self.regs.set_flags(Flags {
+            n: false,
+            h,
+            c: res > 0xFFFF,
+            ..self.regs.flags()
+        });
+    }
 
-            let lo: u16 = reg_value & 0xFF;
-            emu_cycle(1);
-            self.stack_push(lo as u8);
+    // SP + signed e8, shared by ADD SP,e8 and LD HL,SP+e8. The flags are
+    // those of an unsigned 8-bit add of e8 to the low byte of SP.
+    fn sp_plus_e8(&mut self, e8: u8) -> u16 {
+        let sp = self.regs.sp;
+        let h = (sp & 0x0F) + (e8 as u16 & 0x0F) > 0x0F;
+        let c = (sp & 0xFF) + e8 as u16 > 0xFF;
 
-            emu_cycle(1);
-        }
+        self.regs.set_flags(Flags::new(false, false, h, c));
+        sp.wrapping_add(e8 as i8 as u16)
     }
 
-    pub fn proc_inc(&mut self) {
-        let (reg1, mode) = if let Some(inst) = &self.cur_inst {
-            (
-                inst.reg_1.clone(),
-                inst.mode.clone(),
-            )
+    pub fn proc_adc(&mut self) {
+        let carry = self.regs.flags().c as u8;
+        self.alu_add(self.fetched_data as u8, carry);
+    }
+
+    pub fn proc_sub(&mut self) {
+        self.regs.a = self.alu_sub(self.fetched_data as u8, 0);
+    }
+
+    pub fn proc_sbc(&mut self) {
+        let carry = self.regs.flags().c as u8;
+        self.regs.a = self.alu_sub(self.fetched_data as u8, carry);
+    }
+
+    pub fn proc_cp(&mut self) {
+        self.alu_sub(self.fetched_data as u8, 0);
+    }
+
+    pub fn proc_and(&mut self) {
+        self.regs.a &= self.fetched_data as u8;
+        self.regs.set_flags(Flags::new(self.regs.a == 0, false, true, false));
+    }
+
+    pub fn proc_or(&mut self) {
+        self.regs.a |= self.fetched_data as u8;
+        self.regs.set_flags(Flags::new(self.regs.a == 0, false, false, false));
+    }
+
+    // A <- A + val + carry
+    fn alu_add(&mut self, val: u8, carry: u8) {
+        let a = self.regs.a;
+        let res = a as u16 + val as u16 + carry as u16;
+        let h = (a & 0x0F) + (val & 0x0F) + carry > 0x0F;
+
+        self.regs.a = res as u8;
+        self.regs.set_flags(Flags::new(res as u8 == 0, false, h, res > 0xFF));
+    }
+
+    // Returns A - val - carry, leaving A untouched so CP can share it.
+    fn alu_sub(&mut self, val: u8, carry: u8) -> u8 {
+        let a = self.regs.a;
+        let res = a.wrapping_sub(val).wrapping_sub(carry);
+        let h = (a & 0x0F) < (val & 0x0F) + carry;
+        let c = (a as u16) < val as u16 + carry as u16;
+
+        self.regs.set_flags(Flags::new(res == 0, true, h, c));
+        res
+    }
+
+    pub fn proc_rlca(&mut self) {
+        let c = self.regs.a & 0x80 != 0;
+        self.regs.a = self.regs.a.rotate_left(1);
+        self.regs.set_flags(Flags::new(false, false, false, c));
+    }
+
+    pub fn proc_rrca(&mut self) {
+        let c = self.regs.a & 1 != 0;
+        self.regs.a = self.regs.a.rotate_right(1);
+        self.regs.set_flags(Flags::new(false, false, false, c));
+    }
+
+    pub fn proc_rla(&mut self) {
+        let c = self.regs.a & 0x80 != 0;
+        self.regs.a = (self.regs.a << 1) | self.regs.flags().c as u8;
+        self.regs.set_flags(Flags::new(false, false, false, c));
+    }
+
+    pub fn proc_rra(&mut self) {
+        let c = self.regs.a & 1 != 0;
+        self.regs.a = (self.regs.a >> 1) | ((self.regs.flags().c as u8) << 7);
+        self.regs.set_flags(Flags::new(false, false, false, c));
+    }
+
+    // Adjusts A back into packed BCD after an ADD/ADC (N clear) or a
+    // SUB/SBC (N set), using H and C to tell which nibbles overflowed.
+    pub fn proc_daa(&mut self) {
+        let flags = self.regs.flags();
+        let mut adjust: u8 = 0;
+        let mut c = false;
+
+        if flags.h || (!flags.n && (self.regs.a & 0x0F) > 0x09) {
+            adjust |= 0x06;
+        }
+
+        if flags.c || (!flags.n && self.regs.a > 0x99) {
+            adjust |= 0x60;
+            c = true;
+        }
+
+        self.regs.a = if flags.n {
+            self.regs.a.wrapping_sub(adjust)
         } else {
-            return;
+            self.regs.a.wrapping_add(adjust)
         };
 
-        if self.is_16_bit(&reg1) {
-            emu_cycle(1);
+        self.regs.set_flags(Flags::new(self.regs.a == 0, flags.n, false, c));
+    }
+
+    pub fn proc_cpl(&mut self) {
+        self.regs.a = !self.regs.a;
+        self.regs.set_flags(Flags {
+            n: true,
+            h: true,
+            ..self.regs.flags()
+        });
+    }
+
+    pub fn proc_scf(&mut self) {
+        self.regs.set_flags(Flags {
+            n: false,
+            h: false,
+            c: true,
+            ..self.regs.flags()
+        });
+    }
+
+    pub fn proc_ccf(&mut self) {
+        let flags = self.regs.flags();
+        self.regs.set_flags(Flags {
+            n: false,
+            h: false,
+            c: !flags.c,
+            ..flags
+        });
+    }
+
+    pub fn proc_cb(&mut self) {
+        let inst = cb_instruction_by_opcode(self.fetched_data as u8);
+        let reg = inst.reg_1;
+        let bit = inst.param;
+        let is_mem = inst.mode == AddrMode::AmMr;
+
+        let val = self.cpu_read_reg8(&reg);
+        if is_mem {
+            self.emu_cycle(1);
         }
 
-        if reg1 == RegType::RtHl && mode == AddrMode::AmMr {
-            let addr = self.cpu_read_reg(&RegType::RtHl);
-            let val = (self.bus_read(addr) as u16 + 1) & 0xFF;
-            self.bus_write(addr, val as u8);
-        } else {
-            let val = self.cpu_read_reg(&reg1) + 1;
-            self.cpu_set_reg(&reg1, val);
+        let flag_c = self.regs.flags().c as u8;
+        let res = match inst.type_in {
+            InType::InBit => {
+                self.regs.set_flags(Flags {
+                    z: val & (1 << bit) == 0,
+                    n: false,
+                    h: true,
+                    ..self.regs.flags()
+                });
+                return;
+            }
+            InType::InRes => val & !(1 << bit),
+            InType::InSet => val | (1 << bit),
+            InType::InRlc => {
+                let res = val.rotate_left(1);
+                self.regs.set_flags(Flags::new(res == 0, false, false, val & 0x80 != 0));
+                res
+            }
+            InType::InRrc => {
+                let res = val.rotate_right(1);
+                self.regs.set_flags(Flags::new(res == 0, false, false, val & 1 != 0));
+                res
+            }
+            InType::InRl => {
+                let res = (val << 1) | flag_c;
+                self.regs.set_flags(Flags::new(res == 0, false, false, val & 0x80 != 0));
+                res
+            }
+            InType::InRr => {
+                let res = (val >> 1) | (flag_c << 7);
+                self.regs.set_flags(Flags::new(res == 0, false, false, val & 1 != 0));
+                res
+            }
+            InType::InSla => {
+                let res = val << 1;
+                self.regs.set_flags(Flags::new(res == 0, false, false, val & 0x80 != 0));
+                res
+            }
+            InType::InSra => {
+                let res = (val >> 1) | (val & 0x80);
+                self.regs.set_flags(Flags::new(res == 0, false, false, val & 1 != 0));
+                res
+            }
+            InType::InSwap => {
+                let res = val.rotate_left(4);
+                self.regs.set_flags(Flags::new(res == 0, false, false, false));
+                res
+            }
+            // InSrl
+            _ => {
+                let res = val >> 1;
+                self.regs.set_flags(Flags::new(res == 0, false, false, val & 1 != 0));
+                res
+            }
+        };
+
+        self.cpu_set_reg8(&reg, res);
+        if is_mem {
+            self.emu_cycle(1);
         }
     }
 
     pub fn proc_rst(&mut self) {
-        if let Some(inst) = &self.cur_inst {
-            self.goto_addr(inst.param as u16, true);
-        }
+        self.goto_addr(self.cur_inst.param as u16, true);
     }
 
     pub fn proc_ret(&mut self) {
-        let needs_extra_cycle = if let Some(inst) = &self.cur_inst {
-            match inst.cond {
-                CondType::CtNone => false,
-                _ => true,
-            }
-        } else {
-            false
-        };
-
-        if needs_extra_cycle {
-            emu_cycle(1);
+        if self.cur_inst.cond != CondType::CtNone {
+            self.emu_cycle(1);
         }
 
         if self.check_condition() {
             let lo: u16 = self.stack_pop() as u16;
-            emu_cycle(1);
+            self.emu_cycle(1);
             let hi: u16 = (self.stack_pop() as u16) << 8;
-            emu_cycle(1);
+            self.emu_cycle(1);
 
             let n = hi | lo;
             self.regs.pc = n;
 
-            emu_cycle(1);
+            self.emu_cycle(1);
         }
     }
 
@@ -230,27 +477,14 @@ impl<'a> CpuContext<'a> {
     }
 
     pub fn check_condition(&self) -> bool {
-        if let Some(inst) = &self.cur_inst {
-            let z: bool = self.get_flag_z();
-            let c: bool = self.get_flag_c();
-
-            match inst.cond {
-                CondType::CtNone => true,
-                CondType::CtC => c,
-                CondType::CtNc => !c,
-                CondType::CtZ => z,
-                CondType::CtNz => !z,
-            }
-        } else {
-            false
+        let Flags { z, c, .. } = self.regs.flags();
+
+        match self.cur_inst.cond {
+            CondType::CtNone => true,
+            CondType::CtC => c,
+            CondType::CtNc => !c,
+            CondType::CtZ => z,
+            CondType::CtNz => !z,
         }
     }
-
-    pub fn get_flag_z(&self) -> bool {
-        bit!(self.regs.f, 7) == 1
-    }
-
-    pub fn get_flag_c(&self) -> bool {
-        bit!(self.regs.f, 4) == 1
-    }
 }
@@ -0,0 +1,192 @@
+use crate::instructions::{
+    cb_instruction_by_opcode, inst_name, instruction_by_opcode, AddrMode, CondType, InType,
+    RegType,
+};
+
+pub fn reg_name(rt: RegType) -> &'static str {
+    match rt {
+        RegType::RtNone => "",
+        RegType::RtA => "A",
+        RegType::RtF => "F",
+        RegType::RtB => "B",
+        RegType::RtC => "C",
+        RegType::RtD => "D",
+        RegType::RtE => "E",
+        RegType::RtH => "H",
+        RegType::RtL => "L",
+        RegType::RtAf => "AF",
+        RegType::RtBc => "BC",
+        RegType::RtDe => "DE",
+        RegType::RtHl => "HL",
+        RegType::RtSp => "SP",
+        RegType::RtPc => "PC",
+    }
+}
+
+pub fn cond_name(ct: CondType) -> &'static str {
+    match ct {
+        CondType::CtNone => "",
+        CondType::CtNz => "NZ",
+        CondType::CtZ => "Z",
+        CondType::CtNc => "NC",
+        CondType::CtC => "C",
+    }
+}
+
+// Signed 8-bit offset as used by ADD SP,e8 and LD HL,SP+e8.
+fn signed_hex(e8: u8) -> String {
+    let n = e8 as i8;
+    if n < 0 {
+        format!("-${:02X}", n.unsigned_abs())
+    } else {
+        format!("${:02X}", n)
+    }
+}
+
+// Length in bytes of an instruction using `mode`, opcode included.
+pub fn inst_len(mode: AddrMode) -> u16 {
+    match mode {
+        AddrMode::AmRD8
+        | AddrMode::AmD8
+        | AddrMode::AmRa8
+        | AddrMode::AmA8r
+        | AddrMode::AmHlspr
+        | AddrMode::AmMrd8 => 2,
+        AddrMode::AmRD16
+        | AddrMode::AmD16
+        | AddrMode::AmD16r
+        | AddrMode::AmA16r
+        | AddrMode::AmRa16 => 3,
+        _ => 1,
+    }
+}
+
+fn disassemble_cb(op: u8) -> String {
+    let inst = cb_instruction_by_opcode(op);
+    let operand = match inst.mode {
+        AddrMode::AmMr => "[HL]",
+        _ => reg_name(inst.reg_1),
+    };
+
+    match inst.type_in {
+        InType::InBit | InType::InRes | InType::InSet => {
+            format!("{} {},{}", inst_name(&inst.type_in), inst.param, operand)
+        }
+        _ => format!("{} {}", inst_name(&inst.type_in), operand),
+    }
+}
+
+// Disassembles the instruction at `addr` into RGBDS syntax, reading memory
+// through `bus`. Returns the text and the instruction length in bytes.
+pub fn disassemble<F: Fn(u16) -> u8>(bus: F, addr: u16) -> (String, u16) {
+    let opcode = bus(addr);
+    let inst = instruction_by_opcode(opcode);
+    let len = inst_len(inst.mode);
+
+    let d8 = bus(addr.wrapping_add(1));
+    let d16 = d8 as u16 | ((bus(addr.wrapping_add(2)) as u16) << 8);
+
+    let r1 = reg_name(inst.reg_1);
+    let r2 = reg_name(inst.reg_2);
+    let mut name = inst_name(&inst.type_in);
+    let mut ops: Vec<String> = Vec::new();
+
+    if inst.cond != CondType::CtNone {
+        ops.push(cond_name(inst.cond).to_string());
+    }
+
+    match inst.type_in {
+        InType::InNone => return (format!("DB ${:02X}", opcode), 1),
+        InType::InCb => return (disassemble_cb(d8), 2),
+        InType::InStop => return (name.to_string(), len),
+        InType::InRst => ops.push(format!("${:02X}", inst.param)),
+        InType::InJr => {
+            let target = addr.wrapping_add(len).wrapping_add(d8 as i8 as u16);
+            ops.push(format!("${:04X}", target));
+        }
+        _ => match inst.mode {
+            AddrMode::AmImp => (),
+            AddrMode::AmR => ops.push(r1.to_string()),
+            AddrMode::AmRr => {
+                ops.push(r1.to_string());
+                ops.push(r2.to_string());
+            }
+            AddrMode::AmRD8 if inst.reg_1 == RegType::RtSp => {
+                ops.push(r1.to_string());
+                ops.push(signed_hex(d8));
+            }
+            AddrMode::AmRD8 => {
+                ops.push(r1.to_string());
+                ops.push(format!("${:02X}", d8));
+            }
+            AddrMode::AmRD16 => {
+                ops.push(r1.to_string());
+                ops.push(format!("${:04X}", d16));
+            }
+            AddrMode::AmD16 => ops.push(format!("${:04X}", d16)),
+            AddrMode::AmD8 => ops.push(format!("${:02X}", d8)),
+            AddrMode::AmMrr => {
+                if inst.reg_1 == RegType::RtC {
+                    name = "LDH";
+                }
+                ops.push(format!("[{}]", r1));
+                ops.push(r2.to_string());
+            }
+            AddrMode::AmRMr => {
+                if inst.reg_2 == RegType::RtC {
+                    name = "LDH";
+                }
+                ops.push(r1.to_string());
+                ops.push(format!("[{}]", r2));
+            }
+            AddrMode::AmRhli => {
+                ops.push(r1.to_string());
+                ops.push("[HL+]".to_string());
+            }
+            AddrMode::AmRhld => {
+                ops.push(r1.to_string());
+                ops.push("[HL-]".to_string());
+            }
+            AddrMode::AmHlir => {
+                ops.push("[HL+]".to_string());
+                ops.push(r2.to_string());
+            }
+            AddrMode::AmHldr => {
+                ops.push("[HL-]".to_string());
+                ops.push(r2.to_string());
+            }
+            AddrMode::AmRa8 => {
+                ops.push(r1.to_string());
+                ops.push(format!("[$FF{:02X}]", d8));
+            }
+            AddrMode::AmA8r => {
+                ops.push(format!("[$FF{:02X}]", d8));
+                ops.push(r2.to_string());
+            }
+            AddrMode::AmHlspr => {
+                ops.push(r1.to_string());
+                let sign = if (d8 as i8) < 0 { "" } else { "+" };
+                ops.push(format!("{}{}{}", r2, sign, signed_hex(d8)));
+            }
+            AddrMode::AmD16r | AddrMode::AmA16r => {
+                ops.push(format!("[${:04X}]", d16));
+                ops.push(r2.to_string());
+            }
+            AddrMode::AmMrd8 => {
+                ops.push(format!("[{}]", r1));
+                ops.push(format!("${:02X}", d8));
+            }
+            AddrMode::AmMr => ops.push(format!("[{}]", r1)),
+            AddrMode::AmRa16 => {
+                ops.push(r1.to_string());
+                ops.push(format!("[${:04X}]", d16));
+            }
+        },
+    }
+
+    if ops.is_empty() {
+        (name.to_string(), len)
+    } else {
+        (format!("{} {}", name, ops.join(",")), len)
+    }
+}
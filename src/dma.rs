@@ -0,0 +1,86 @@
+use crate::bus::BusContext;
+use crate::ram::RamReadWrite;
+
+// OAM DMA, started by writing the source page to 0xFF46. After one M-cycle
+// of setup it copies 0xXX00 - 0xXX9F into OAM at a byte per M-cycle, and
+// the CPU can only reach 0xFF00 - 0xFFFF meanwhile. Writing 0xFF46 again
+// restarts the copy once the new setup cycle is over, the old one keeps
+// the bus until then.
+pub struct DmaContext {
+    pub active: bool,
+    // Last value written to 0xFF46, the next transfer's source.
+    pub page: u8,
+    // Page the running transfer copies from.
+    pub source: u8,
+    pub byte: u8,
+    // Byte on the bus, what blocked CPU reads see.
+    pub value: u8,
+    pub start_delay: u8,
+}
+
+impl Default for DmaContext {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl DmaContext {
+    pub fn new() -> Self {
+        DmaContext {
+            active: false,
+            page: 0xFF,
+            source: 0,
+            byte: 0,
+            value: 0xFF,
+            start_delay: 0,
+        }
+    }
+
+    pub fn dma_start(&mut self, page: u8) {
+        self.page = page;
+        // Counts down the M-cycle of the write and the setup cycle.
+        self.start_delay = 2;
+    }
+
+    pub fn dma_transferring(&self) -> bool {
+        self.active
+    }
+
+    // Whether a CPU access to `address` loses the bus to the DMA.
+    pub fn dma_blocks(&self, address: u16) -> bool {
+        self.active && address < 0xFF00
+    }
+}
+
+impl<'a> BusContext<'a> {
+    // Runs the DMA for one M-cycle.
+    pub fn dma_tick(&mut self) {
+        if self.dma.active {
+            let source = u16::from_be_bytes([self.dma.source, self.dma.byte]);
+            self.dma.value = self.dma_source_read(source);
+            self.ppu.vmem.oam[self.dma.byte as usize] = self.dma.value;
+
+            self.dma.byte += 1;
+            self.dma.active = self.dma.byte < 0xA0;
+        }
+
+        if self.dma.start_delay > 0 {
+            self.dma.start_delay -= 1;
+            if self.dma.start_delay == 0 {
+                self.dma.active = true;
+                self.dma.byte = 0;
+                self.dma.source = self.dma.page;
+            }
+        }
+    }
+
+    // The DMA reads past the PPU's locks, and 0xE000 and up comes from
+    // work RAM.
+    fn dma_source_read(&self, address: u16) -> u8 {
+        match address {
+            0x8000..=0x9FFF => self.ppu.vmem.vram[self.ppu.vmem.vram_index(address)],
+            0xE000..=0xFFFF => self.ram.wram_read(address - 0x2000),
+            _ => self.bus_read(address),
+        }
+    }
+}
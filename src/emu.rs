@@ -1,44 +1,48 @@
-use crate::{cart, common, cpu::{self, CpuContext}};
+use crate::{
+    bus::BusContext,
+    cart, common,
+    cpu::CpuContext,
+    error::EmuError,
+    model::Model,
+    trace::{self, TraceCompareContext, TraceContext, TraceFormat},
+};
 
 use sdl2::{
     self,
-    sys::{ttf::TTF_Init, SDL_Delay, SDL_Init, SDL_INIT_VIDEO},
+    sys::{ttf::TTF_Init, SDL_Init, SDL_INIT_VIDEO},
 };
 
-pub struct emu_context {
+// Instructions shown before a divergence from a reference trace.
+const TRACE_HISTORY: usize = 16;
+
+pub struct EmuContext {
     paused: bool,
     running: bool,
     ticks: u64,
 }
 
-impl emu_context {
+impl Default for EmuContext {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl EmuContext {
     pub fn new() -> Self {
-        emu_context {
+        EmuContext {
             paused: false,
             running: false,
             ticks: 0,
         }
     }
 
-    fn delay(ms: u32) {
-        unsafe {
-            SDL_Delay(ms);
-        }
-    }
-
-    pub fn emu_run(&mut self, argv: Vec<String>) {
+    pub fn emu_run(&mut self, argv: Vec<String>) -> Result<(), EmuError> {
         if argv.len() < 2 {
-            panic!("Usage: emu <rom_file>\n");
+            return Err(EmuError::Usage);
         }
 
         let mut cart: cart::CartContext = cart::CartContext::new();
-        if let Ok(val) = cart.cart_load(&argv[1]) {
-            if !val {
-                panic!("Failed to load ROM file: {}", &argv[1]);
-            }
-        } else {
-            panic!("Failed to load ROM file: {}", &argv[1]);
-        }
+        cart.cart_load(&argv[1])?;
 
         println!("Cart loaded..");
 
@@ -49,7 +53,48 @@ impl emu_context {
             println!("TTF INIT");
         }
 
-        let mut cpu: CpuContext = CpuContext::new(&mut cart);
+        let mut cpu = CpuContext::new(BusContext::new(&mut cart, Model::Dmg));
+        cpu.cpu_init(Model::Dmg);
+
+        // emu <rom_file> [--trace <log> | --trace-rich <log> | --compare <log>]
+        //     [--trace-pc <start>-<end>] [--trace-frames <start>-<end>]
+        let mut pc_range = None;
+        let mut frame_range = None;
+        let mut options = argv[2..].iter();
+        while let Some(flag) = options.next() {
+            let value = options.next().ok_or(EmuError::Usage)?;
+            match flag.as_str() {
+                "--trace" | "--trace-rich" => {
+                    let format = if flag == "--trace" {
+                        TraceFormat::Doctor
+                    } else {
+                        TraceFormat::Rich
+                    };
+                    let tracer = TraceContext::to_file(format, value).map_err(EmuError::TraceIo)?;
+                    cpu.tracer = Some(tracer);
+                }
+                "--compare" => {
+                    let compare = TraceCompareContext::from_file(value, TRACE_HISTORY)
+                        .map_err(EmuError::TraceIo)?;
+                    cpu.trace_compare = Some(compare);
+                }
+                "--trace-pc" => {
+                    pc_range = Some(trace::parse_pc_range(value).ok_or(EmuError::Usage)?)
+                }
+                "--trace-frames" => {
+                    frame_range = Some(trace::parse_frame_range(value).ok_or(EmuError::Usage)?)
+                }
+                _ => return Err(EmuError::Usage),
+            }
+        }
+
+        // The filters only apply to a trace being written.
+        if let Some(tracer) = &mut cpu.tracer {
+            tracer.pc_range = pc_range;
+            tracer.frame_range = frame_range;
+        } else if pc_range.is_some() || frame_range.is_some() {
+            return Err(EmuError::Usage);
+        }
 
         self.running = true;
         self.paused = false;
@@ -61,11 +106,14 @@ impl emu_context {
                 continue;
             }
 
-            if !cpu.cpu_step() {
-                panic!("CPU Stopped");
+            match cpu.cpu_step() {
+                Ok(cycles) => self.ticks += cycles as u64,
+                // A locked up CPU is still a running Game Boy.
+                Err(err @ EmuError::IllegalOpcode { .. }) => eprintln!("{}", err),
+                Err(err) => return Err(err),
             }
-
-            self.ticks += 1;
         }
+
+        Ok(())
     }
 }
@@ -0,0 +1,54 @@
+use std::fmt;
+use std::io;
+
+use crate::instructions::RegType;
+use crate::trace::TraceMismatch;
+
+// Everything that can go wrong while loading or running a cartridge.
+#[derive(Debug)]
+pub enum EmuError {
+    // No ROM path was given on the command line, or an option was malformed.
+    Usage,
+    CartLoad { path: String, source: io::Error },
+    // The CPU hit one of the 11 unused opcodes and locked up. `pc` is the
+    // address of the opcode itself.
+    IllegalOpcode { opcode: u8, pc: u16 },
+    InvalidRegister(RegType),
+    TraceIo(io::Error),
+    // The run no longer matches the reference trace being compared.
+    TraceMismatch(Box<TraceMismatch>),
+}
+
+impl fmt::Display for EmuError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            EmuError::Usage => write!(
+                f,
+                "usage: emu [--headless] <rom_file> [--trace <log> | --trace-rich <log> | --compare <log>] \
+                 [--trace-pc <start>-<end>] [--trace-frames <start>-<end>]"
+            ),
+            EmuError::CartLoad { path, source } => {
+                write!(f, "failed to load ROM file {}: {}", path, source)
+            }
+            EmuError::IllegalOpcode { opcode, pc } => {
+                write!(
+                    f,
+                    "illegal opcode {:02X} at {:04X}, CPU locked up",
+                    opcode, pc
+                )
+            }
+            EmuError::InvalidRegister(rt) => write!(f, "invalid 8-bit register {:?}", rt),
+            EmuError::TraceIo(source) => write!(f, "trace file error: {}", source),
+            EmuError::TraceMismatch(mismatch) => write!(f, "{}", mismatch),
+        }
+    }
+}
+
+impl std::error::Error for EmuError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            EmuError::CartLoad { source, .. } | EmuError::TraceIo(source) => Some(source),
+            _ => None,
+        }
+    }
+}
@@ -0,0 +1,126 @@
+use crate::bus::BusContext;
+
+// CGB VRAM DMA. HDMA1-4 (0xFF51 - 0xFF54) hold the source and the VRAM
+// destination, 16 byte aligned. Writing HDMA5 (0xFF55) starts a copy of
+// (value & 0x7F) + 1 blocks of 16 bytes:
+//
+// - bit 7 clear, general purpose DMA: everything is copied at once.
+// - bit 7 set, HBlank DMA: one block at the start of each HBlank. Writing
+//   bit 7 clear while it runs stops it.
+//
+// The CPU is held while a block is copied, 2 bytes per M-cycle in normal
+// speed and 1 in double speed. With the LCD off there are no HBlanks, so
+// an HBlank DMA copies one block when started and then waits for the LCD.
+pub struct HdmaContext {
+    pub source: u16,
+    pub dest: u16,
+    // Blocks left minus one, 0x7F once done. The low bits of HDMA5.
+    pub length: u8,
+    pub hblank_active: bool,
+    // Bytes still to copy before the CPU gets the bus back.
+    pub pending: u16,
+}
+
+impl Default for HdmaContext {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl HdmaContext {
+    pub fn new() -> Self {
+        HdmaContext {
+            source: 0,
+            dest: 0x8000,
+            length: 0x7F,
+            hblank_active: false,
+            pending: 0,
+        }
+    }
+
+    // Only HDMA5 reads back, as the blocks left with bit 7 clear while an
+    // HBlank DMA runs.
+    pub fn hdma_read(&self, address: u16) -> u8 {
+        match address {
+            0xFF55 if self.hblank_active => self.length,
+            0xFF55 => 0x80 | self.length,
+            _ => 0xFF,
+        }
+    }
+
+    // `in_hblank` is whether the PPU is in HBlank (or off) right now, an
+    // HBlank DMA started then copies its first block straight away.
+    pub fn hdma_write(&mut self, address: u16, value: u8, in_hblank: bool) {
+        match address {
+            0xFF51 => self.source = (self.source & 0x00F0) | (value as u16) << 8,
+            0xFF52 => self.source = (self.source & 0xFF00) | (value & 0xF0) as u16,
+            0xFF53 => self.dest = 0x8000 | (self.dest & 0x00F0) | ((value & 0x1F) as u16) << 8,
+            0xFF54 => self.dest = (self.dest & 0xFF00) | (value & 0xF0) as u16,
+            _ => {
+                if self.hblank_active && value & 0x80 == 0 {
+                    self.hblank_active = false;
+                    return;
+                }
+
+                self.length = value & 0x7F;
+                if value & 0x80 != 0 {
+                    self.hblank_active = true;
+                    if in_hblank {
+                        self.pending = 0x10;
+                    }
+                } else {
+                    self.pending = (self.length as u16 + 1) * 0x10;
+                }
+            }
+        }
+    }
+
+    // Called when the PPU enters HBlank on a visible line.
+    pub fn hdma_hblank(&mut self) {
+        if self.hblank_active && self.pending == 0 {
+            self.pending = 0x10;
+        }
+    }
+
+    pub fn hdma_stalled(&self) -> bool {
+        self.pending > 0
+    }
+
+    fn hdma_block_done(&mut self) {
+        if self.length == 0 {
+            self.length = 0x7F;
+            self.hblank_active = false;
+        } else {
+            self.length -= 1;
+        }
+    }
+}
+
+impl<'a> BusContext<'a> {
+    // Runs the VRAM DMA for one M-cycle.
+    pub fn hdma_tick(&mut self) {
+        let bytes = if self.double_speed() { 1 } else { 2 };
+
+        for _ in 0..bytes {
+            if self.hdma.pending == 0 {
+                return;
+            }
+
+            // VRAM can't be a source, the copy sees open bus.
+            let source = self.hdma.source;
+            let value = match source {
+                0x8000..=0x9FFF => 0xFF,
+                _ => self.bus_read(source),
+            };
+            let index = self.ppu.vmem.vram_index(self.hdma.dest);
+            self.ppu.vmem.vram[index] = value;
+
+            self.hdma.source = source.wrapping_add(1);
+            self.hdma.dest = 0x8000 | (self.hdma.dest.wrapping_add(1) & 0x1FFF);
+            self.hdma.pending -= 1;
+            if self.hdma.dest & 0x0F == 0 {
+                self.hdma.hdma_block_done();
+            }
+        }
+    }
+}
@@ -0,0 +1,175 @@
+use crate::bus::{Bus, BusContext};
+use crate::cart::CartContext;
+use crate::cpu::CpuContext;
+use crate::error::EmuError;
+use crate::model::Model;
+use crate::serial::SerialCapture;
+
+// Blargg's ROMs report through cartridge RAM as well as serial: 0xA000
+// holds 0x80 while running and the result code after, 0xA001-0xA003 carry
+// the signature and the text starts at 0xA004.
+const STATUS_ADDR: u16 = 0xA000;
+const SIGNATURE: [u8; 3] = [0xDE, 0xB0, 0x61];
+const TEXT_ADDR: u16 = 0xA004;
+const STATUS_RUNNING: u8 = 0x80;
+
+// Mooneye's ROMs finish with LD B,B, leaving the Fibonacci sequence in
+// B, C, D, E, H and L when they pass.
+const MOONEYE_DONE: u8 = 0x40;
+const MOONEYE_PASS: [u8; 6] = [3, 5, 8, 13, 21, 34];
+
+// Enough for the slowest Blargg ROMs, about two minutes of emulated time.
+pub const MAX_CYCLES: u64 = 120 * 1_048_576;
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum TestStatus {
+    Passed,
+    Failed,
+    TimedOut,
+}
+
+pub struct TestReport {
+    pub status: TestStatus,
+    pub serial: String,
+    pub memory: String,
+    // M-cycles run.
+    pub cycles: u64,
+}
+
+fn memory_signature<B: Bus>(bus: &B) -> bool {
+    (0..3).all(|i| bus.peek(STATUS_ADDR + 1 + i) == SIGNATURE[i as usize])
+}
+
+fn memory_text<B: Bus>(bus: &B) -> String {
+    (TEXT_ADDR..0xC000)
+        .map(|address| bus.peek(address))
+        .take_while(|&b| b != 0)
+        .map(|b| b as char)
+        .collect()
+}
+
+fn text_status(text: &str) -> Option<TestStatus> {
+    if text.contains("Passed") {
+        Some(TestStatus::Passed)
+    } else if text.contains("Failed") {
+        Some(TestStatus::Failed)
+    } else {
+        None
+    }
+}
+
+// Runs a Blargg test ROM without a window until it reports a result over
+// serial or in cartridge RAM, or `max_cycles` M-cycles have gone by.
+pub fn run_test_rom(path: &str, model: Model, max_cycles: u64) -> Result<TestReport, EmuError> {
+    let mut cart = CartContext::new();
+    cart.cart_load(path)?;
+
+    let capture = SerialCapture::default();
+    let mut bus = BusContext::new(&mut cart, model);
+    bus.serial.serial_connect(Box::new(capture.clone()));
+
+    let mut cpu = CpuContext::new(bus);
+    cpu.cpu_init(model);
+
+    let mut status = TestStatus::TimedOut;
+    let mut serial_len = 0;
+
+    // A stopped CPU doesn't advance the clock, so bound the steps as well.
+    let mut steps = 0;
+    while cpu.ticks < max_cycles && steps < max_cycles {
+        steps += 1;
+        cpu.cpu_step()?;
+
+        // Only rescan the serial text when something new came in.
+        if capture.output.borrow().len() != serial_len {
+            serial_len = capture.output.borrow().len();
+            if let Some(s) = text_status(&capture.text()) {
+                status = s;
+                break;
+            }
+        }
+
+        let code = cpu.bus.peek(STATUS_ADDR);
+        if code != STATUS_RUNNING && memory_signature(&cpu.bus) {
+            status = match text_status(&memory_text(&cpu.bus)) {
+                Some(s) => s,
+                None if code == 0 => TestStatus::Passed,
+                None => TestStatus::Failed,
+            };
+            break;
+        }
+    }
+
+    Ok(TestReport {
+        status,
+        serial: capture.text(),
+        memory: memory_text(&cpu.bus),
+        cycles: cpu.ticks,
+    })
+}
+
+// Runs a Mooneye test ROM until it executes LD B,B or `max_cycles` M-cycles
+// have gone by.
+pub fn run_mooneye_rom(path: &str, model: Model, max_cycles: u64) -> Result<TestStatus, EmuError> {
+    let mut cart = CartContext::new();
+    cart.cart_load(path)?;
+
+    let mut cpu = CpuContext::new(BusContext::new(&mut cart, model));
+    cpu.cpu_init(model);
+
+    let mut steps = 0;
+    while cpu.ticks < max_cycles && steps < max_cycles {
+        steps += 1;
+        cpu.cpu_step()?;
+
+        if cpu.cur_opcode == MOONEYE_DONE {
+            let r = &cpu.regs;
+            return Ok(if [r.b, r.c, r.d, r.e, r.h, r.l] == MOONEYE_PASS {
+                TestStatus::Passed
+            } else {
+                TestStatus::Failed
+            });
+        }
+    }
+
+    Ok(TestStatus::TimedOut)
+}
+
+// Models a Mooneye ROM is meant for, from the suffix of its file name
+// (`boot_regs-dmgABC`, `di_timing-GS`...). Unsuffixed tests pass on every
+// model. Revisions and models that aren't emulated are dropped.
+pub fn mooneye_models(stem: &str) -> Vec<Model> {
+    const TAGS: [(&str, Option<Model>); 16] = [
+        ("dmgABC", Some(Model::Dmg)),
+        ("dmg0", None),
+        ("mgb", None),
+        ("sgb2", None),
+        ("sgb", None),
+        ("cgbABCDE", Some(Model::Cgb)),
+        ("cgb0", None),
+        ("cgb", Some(Model::Cgb)),
+        ("agb0", None),
+        ("agbA", None),
+        ("agb", None),
+        ("ags", None),
+        ("G", Some(Model::Dmg)),
+        ("S", None),
+        ("C", Some(Model::Cgb)),
+        ("A", None),
+    ];
+
+    let Some((_, mut suffix)) = stem.rsplit_once('-') else {
+        return vec![Model::Dmg, Model::Cgb];
+    };
+
+    let mut models = Vec::new();
+    while let Some((tag, model)) = TAGS.iter().find(|(tag, _)| suffix.starts_with(tag)) {
+        suffix = &suffix[tag.len()..];
+        if let Some(model) = model {
+            if !models.contains(model) {
+                models.push(*model);
+            }
+        }
+    }
+    models
+}
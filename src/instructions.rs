@@ -1,4 +1,4 @@
-#[derive(Clone, Debug, PartialEq, PartialOrd)]
+#[derive(Clone, Copy, Debug, PartialEq, PartialOrd)]
 pub enum AddrMode {
     AmImp,
     AmRD16,
@@ -23,7 +23,7 @@ pub enum AddrMode {
     AmRa16,
 }
 
-#[derive(Clone, Debug, PartialEq, PartialOrd)]
+#[derive(Clone, Copy, Debug, PartialEq, PartialOrd)]
 pub enum RegType {
     RtNone,
     RtA,
@@ -43,7 +43,7 @@ pub enum RegType {
 }
 
 
-#[derive(Clone, Debug)]
+#[derive(Clone, Copy, Debug, PartialEq)]
 pub enum InType {
     InNone,
     InNop,
@@ -96,7 +96,7 @@ pub enum InType {
     InSet,
 }
 
-#[derive(Clone)]
+#[derive(Clone, Copy, Debug, PartialEq)]
 pub enum CondType {
     CtNone,
     CtNz,
@@ -105,7 +105,7 @@ pub enum CondType {
     CtC,
 }
 
-#[derive(Clone)]
+#[derive(Clone, Copy, Debug, PartialEq)]
 pub struct Instruction {
     pub type_in: InType,
     pub mode: AddrMode,
@@ -115,1962 +115,378 @@ pub struct Instruction {
     pub param: u8,
 }
 
-pub fn instruction_by_opcode(opcode: u8) -> Option<Instruction> {
-    match opcode {
-        0x00 => Some(Instruction {
-            type_in: InType::InNop,
-            mode: AddrMode::AmImp,
-            reg_1: RegType::RtNone,
-            reg_2: RegType::RtNone,
-            cond: CondType::CtNone,
-            param: 0,
-        }),
-        0x01 => Some(Instruction {
-            type_in: InType::InLd,
-            mode: AddrMode::AmRD16,
-            reg_1: RegType::RtBc,
-            reg_2: RegType::RtNone,
-            cond: CondType::CtNone,
-            param: 0,
-        }),
-        0x02 => Some(Instruction {
-            type_in: InType::InLd,
-            mode: AddrMode::AmMrr,
-            reg_1: RegType::RtBc,
-            reg_2: RegType::RtA,
-            cond: CondType::CtNone,
-            param: 0,
-        }),
-        0x03 => Some(Instruction {
-            type_in: InType::InInc,
-            mode: AddrMode::AmR,
-            reg_1: RegType::RtBc,
-            reg_2: RegType::RtNone,
-            cond: CondType::CtNone,
-            param: 0,
-        }),
-        0x04 => Some(Instruction {
-            type_in: InType::InInc,
-            mode: AddrMode::AmR,
-            reg_1: RegType::RtB,
-            reg_2: RegType::RtNone,
-            cond: CondType::CtNone,
-            param: 0,
-        }),
-        0x05 => Some(Instruction {
-            type_in: InType::InDec,
-            mode: AddrMode::AmR,
-            reg_1: RegType::RtB,
-            reg_2: RegType::RtNone,
-            cond: CondType::CtNone,
-            param: 0,
-        }),
-        0x06 => Some(Instruction {
-            type_in: InType::InLd,
-            mode: AddrMode::AmRD8,
-            reg_1: RegType::RtB,
-            reg_2: RegType::RtNone,
-            cond: CondType::CtNone,
-            param: 0,
-        }),
-        0x07 => Some(Instruction {
-            type_in: InType::InRlca,
-            mode: AddrMode::AmImp,
-            reg_1: RegType::RtNone,
-            reg_2: RegType::RtNone,
-            cond: CondType::CtNone,
-            param: 0,
-        }),
-        0x08 => Some(Instruction {
-            type_in: InType::InLd,
-            mode: AddrMode::AmA16r,
-            reg_1: RegType::RtNone,
-            reg_2: RegType::RtSp,
-            cond: CondType::CtNone,
-            param: 0,
-        }),
-        0x09 => Some(Instruction {
-            type_in: InType::InAdd,
-            mode: AddrMode::AmRr,
-            reg_1: RegType::RtHl,
-            reg_2: RegType::RtBc,
-            cond: CondType::CtNone,
-            param: 0,
-        }),
-        0x0A => Some(Instruction {
-            type_in: InType::InLd,
-            mode: AddrMode::AmRMr,
-            reg_1: RegType::RtA,
-            reg_2: RegType::RtBc,
-            cond: CondType::CtNone,
-            param: 0,
-        }),
-        0x0B => Some(Instruction {
-            type_in: InType::InDec,
-            mode: AddrMode::AmR,
-            reg_1: RegType::RtBc,
-            reg_2: RegType::RtNone,
-            cond: CondType::CtNone,
-            param: 0,
-        }),
-        0x0C => Some(Instruction {
-            type_in: InType::InInc,
-            mode: AddrMode::AmR,
-            reg_1: RegType::RtC,
-            reg_2: RegType::RtNone,
-            cond: CondType::CtNone,
-            param: 0,
-        }),
-        0x0D => Some(Instruction {
-            type_in: InType::InDec,
-            mode: AddrMode::AmR,
-            reg_1: RegType::RtC,
-            reg_2: RegType::RtNone,
-            cond: CondType::CtNone,
-            param: 0,
-        }),
-        0x0E => Some(Instruction {
-            type_in: InType::InLd,
-            mode: AddrMode::AmRD8,
-            reg_1: RegType::RtC,
-            reg_2: RegType::RtNone,
-            cond: CondType::CtNone,
-            param: 0,
-        }),
-        0x0F => Some(Instruction {
-            type_in: InType::InRrca,
-            mode: AddrMode::AmImp,
-            reg_1: RegType::RtNone,
-            reg_2: RegType::RtNone,
-            cond: CondType::CtNone,
-            param: 0,
-        }),
+const fn inst(
+    type_in: InType,
+    mode: AddrMode,
+    reg_1: RegType,
+    reg_2: RegType,
+    cond: CondType,
+    param: u8,
+) -> Instruction {
+    Instruction {
+        type_in,
+        mode,
+        reg_1,
+        reg_2,
+        cond,
+        param,
+    }
+}
 
-        // 0x1X
-        0x10 => Some(Instruction {
-            type_in: InType::InStop,
-            mode: AddrMode::AmImp,
-            reg_1: RegType::RtNone,
-            reg_2: RegType::RtNone,
-            cond: CondType::CtNone,
-            param: 0,
-        }),
-        0x11 => Some(Instruction {
-            type_in: InType::InLd,
-            mode: AddrMode::AmRD16,
-            reg_1: RegType::RtDe,
-            reg_2: RegType::RtNone,
-            cond: CondType::CtNone,
-            param: 0,
-        }),
-        0x12 => Some(Instruction {
-            type_in: InType::InLd,
-            mode: AddrMode::AmMrr,
-            reg_1: RegType::RtDe,
-            reg_2: RegType::RtA,
-            cond: CondType::CtNone,
-            param: 0,
-        }),
-        0x13 => Some(Instruction {
-            type_in: InType::InInc,
-            mode: AddrMode::AmR,
-            reg_1: RegType::RtDe,
-            reg_2: RegType::RtNone,
-            cond: CondType::CtNone,
-            param: 0,
-        }),
-        0x14 => Some(Instruction {
-            type_in: InType::InInc,
-            mode: AddrMode::AmR,
-            reg_1: RegType::RtD,
-            reg_2: RegType::RtNone,
-            cond: CondType::CtNone,
-            param: 0,
-        }),
-        0x15 => Some(Instruction {
-            type_in: InType::InDec,
-            mode: AddrMode::AmR,
-            reg_1: RegType::RtD,
-            reg_2: RegType::RtNone,
-            cond: CondType::CtNone,
-            param: 0,
-        }),
-        0x16 => Some(Instruction {
-            type_in: InType::InLd,
-            mode: AddrMode::AmRD8,
-            reg_1: RegType::RtD,
-            reg_2: RegType::RtNone,
-            cond: CondType::CtNone,
-            param: 0,
-        }),
-        0x17 => Some(Instruction {
-            type_in: InType::InRla,
-            mode: AddrMode::AmImp,
-            reg_1: RegType::RtNone,
-            reg_2: RegType::RtNone,
-            cond: CondType::CtNone,
-            param: 0,
-        }),
-        0x18 => Some(Instruction {
-            type_in: InType::InJr,
-            mode: AddrMode::AmD8,
-            reg_1: RegType::RtNone,
-            reg_2: RegType::RtNone,
-            cond: CondType::CtNone,
-            param: 0,
-        }),
-        0x19 => Some(Instruction {
-            type_in: InType::InAdd,
-            mode: AddrMode::AmRr,
-            reg_1: RegType::RtHl,
-            reg_2: RegType::RtDe,
-            cond: CondType::CtNone,
-            param: 0,
-        }),
-        0x1A => Some(Instruction {
-            type_in: InType::InLd,
-            mode: AddrMode::AmRMr,
-            reg_1: RegType::RtA,
-            reg_2: RegType::RtDe,
-            cond: CondType::CtNone,
-            param: 0,
-        }),
-        0x1B => Some(Instruction {
-            type_in: InType::InDec,
-            mode: AddrMode::AmR,
-            reg_1: RegType::RtDe,
-            reg_2: RegType::RtNone,
-            cond: CondType::CtNone,
-            param: 0,
-        }),
-        0x1C => Some(Instruction {
-            type_in: InType::InInc,
-            mode: AddrMode::AmR,
-            reg_1: RegType::RtE,
-            reg_2: RegType::RtNone,
-            cond: CondType::CtNone,
-            param: 0,
-        }),
-        0x1D => Some(Instruction {
-            type_in: InType::InDec,
-            mode: AddrMode::AmR,
-            reg_1: RegType::RtE,
-            reg_2: RegType::RtNone,
-            cond: CondType::CtNone,
-            param: 0,
-        }),
-        0x1E => Some(Instruction {
-            type_in: InType::InLd,
-            mode: AddrMode::AmRD8,
-            reg_1: RegType::RtE,
-            reg_2: RegType::RtNone,
-            cond: CondType::CtNone,
-            param: 0,
-        }),
-        0x1F => Some(Instruction {
-            type_in: InType::InRra,
-            mode: AddrMode::AmImp,
-            reg_1: RegType::RtNone,
-            reg_2: RegType::RtNone,
-            cond: CondType::CtNone,
-            param: 0,
-        }),
+// Unused opcodes decode to InNone.
+const NONE: Instruction = inst(
+    InType::InNone,
+    AddrMode::AmImp,
+    RegType::RtNone,
+    RegType::RtNone,
+    CondType::CtNone,
+    0,
+);
+
+// Indexed by opcode.
+#[rustfmt::skip]
+pub const INSTRUCTIONS: [Instruction; 256] = [
+    inst(InType::InNop, AddrMode::AmImp, RegType::RtNone, RegType::RtNone, CondType::CtNone, 0), // 0x00
+    inst(InType::InLd, AddrMode::AmRD16, RegType::RtBc, RegType::RtNone, CondType::CtNone, 0), // 0x01
+    inst(InType::InLd, AddrMode::AmMrr, RegType::RtBc, RegType::RtA, CondType::CtNone, 0), // 0x02
+    inst(InType::InInc, AddrMode::AmR, RegType::RtBc, RegType::RtNone, CondType::CtNone, 0), // 0x03
+    inst(InType::InInc, AddrMode::AmR, RegType::RtB, RegType::RtNone, CondType::CtNone, 0), // 0x04
+    inst(InType::InDec, AddrMode::AmR, RegType::RtB, RegType::RtNone, CondType::CtNone, 0), // 0x05
+    inst(InType::InLd, AddrMode::AmRD8, RegType::RtB, RegType::RtNone, CondType::CtNone, 0), // 0x06
+    inst(InType::InRlca, AddrMode::AmImp, RegType::RtNone, RegType::RtNone, CondType::CtNone, 0), // 0x07
+    inst(InType::InLd, AddrMode::AmA16r, RegType::RtNone, RegType::RtSp, CondType::CtNone, 0), // 0x08
+    inst(InType::InAdd, AddrMode::AmRr, RegType::RtHl, RegType::RtBc, CondType::CtNone, 0), // 0x09
+    inst(InType::InLd, AddrMode::AmRMr, RegType::RtA, RegType::RtBc, CondType::CtNone, 0), // 0x0A
+    inst(InType::InDec, AddrMode::AmR, RegType::RtBc, RegType::RtNone, CondType::CtNone, 0), // 0x0B
+    inst(InType::InInc, AddrMode::AmR, RegType::RtC, RegType::RtNone, CondType::CtNone, 0), // 0x0C
+    inst(InType::InDec, AddrMode::AmR, RegType::RtC, RegType::RtNone, CondType::CtNone, 0), // 0x0D
+    inst(InType::InLd, AddrMode::AmRD8, RegType::RtC, RegType::RtNone, CondType::CtNone, 0), // 0x0E
+    inst(InType::InRrca, AddrMode::AmImp, RegType::RtNone, RegType::RtNone, CondType::CtNone, 0), // 0x0F
+    inst(InType::InStop, AddrMode::AmD8, RegType::RtNone, RegType::RtNone, CondType::CtNone, 0), // 0x10
+    inst(InType::InLd, AddrMode::AmRD16, RegType::RtDe, RegType::RtNone, CondType::CtNone, 0), // 0x11
+    inst(InType::InLd, AddrMode::AmMrr, RegType::RtDe, RegType::RtA, CondType::CtNone, 0), // 0x12
+    inst(InType::InInc, AddrMode::AmR, RegType::RtDe, RegType::RtNone, CondType::CtNone, 0), // 0x13
+    inst(InType::InInc, AddrMode::AmR, RegType::RtD, RegType::RtNone, CondType::CtNone, 0), // 0x14
+    inst(InType::InDec, AddrMode::AmR, RegType::RtD, RegType::RtNone, CondType::CtNone, 0), // 0x15
+    inst(InType::InLd, AddrMode::AmRD8, RegType::RtD, RegType::RtNone, CondType::CtNone, 0), // 0x16
+    inst(InType::InRla, AddrMode::AmImp, RegType::RtNone, RegType::RtNone, CondType::CtNone, 0), // 0x17
+    inst(InType::InJr, AddrMode::AmD8, RegType::RtNone, RegType::RtNone, CondType::CtNone, 0), // 0x18
+    inst(InType::InAdd, AddrMode::AmRr, RegType::RtHl, RegType::RtDe, CondType::CtNone, 0), // 0x19
+    inst(InType::InLd, AddrMode::AmRMr, RegType::RtA, RegType::RtDe, CondType::CtNone, 0), // 0x1A
+    inst(InType::InDec, AddrMode::AmR, RegType::RtDe, RegType::RtNone, CondType::CtNone, 0), // 0x1B
+    inst(InType::InInc, AddrMode::AmR, RegType::RtE, RegType::RtNone, CondType::CtNone, 0), // 0x1C
+    inst(InType::InDec, AddrMode::AmR, RegType::RtE, RegType::RtNone, CondType::CtNone, 0), // 0x1D
+    inst(InType::InLd, AddrMode::AmRD8, RegType::RtE, RegType::RtNone, CondType::CtNone, 0), // 0x1E
+    inst(InType::InRra, AddrMode::AmImp, RegType::RtNone, RegType::RtNone, CondType::CtNone, 0), // 0x1F
+    inst(InType::InJr, AddrMode::AmD8, RegType::RtNone, RegType::RtNone, CondType::CtNz, 0), // 0x20
+    inst(InType::InLd, AddrMode::AmRD16, RegType::RtHl, RegType::RtNone, CondType::CtNone, 0), // 0x21
+    inst(InType::InLd, AddrMode::AmHlir, RegType::RtHl, RegType::RtA, CondType::CtNone, 0), // 0x22
+    inst(InType::InInc, AddrMode::AmR, RegType::RtHl, RegType::RtNone, CondType::CtNone, 0), // 0x23
+    inst(InType::InInc, AddrMode::AmR, RegType::RtH, RegType::RtNone, CondType::CtNone, 0), // 0x24
+    inst(InType::InDec, AddrMode::AmR, RegType::RtH, RegType::RtNone, CondType::CtNone, 0), // 0x25
+    inst(InType::InLd, AddrMode::AmRD8, RegType::RtH, RegType::RtNone, CondType::CtNone, 0), // 0x26
+    inst(InType::InDaa, AddrMode::AmImp, RegType::RtNone, RegType::RtNone, CondType::CtNone, 0), // 0x27
+    inst(InType::InJr, AddrMode::AmD8, RegType::RtNone, RegType::RtNone, CondType::CtZ, 0), // 0x28
+    inst(InType::InAdd, AddrMode::AmRr, RegType::RtHl, RegType::RtHl, CondType::CtNone, 0), // 0x29
+    inst(InType::InLd, AddrMode::AmRhli, RegType::RtA, RegType::RtHl, CondType::CtNone, 0), // 0x2A
+    inst(InType::InDec, AddrMode::AmR, RegType::RtHl, RegType::RtNone, CondType::CtNone, 0), // 0x2B
+    inst(InType::InInc, AddrMode::AmR, RegType::RtL, RegType::RtNone, CondType::CtNone, 0), // 0x2C
+    inst(InType::InDec, AddrMode::AmR, RegType::RtL, RegType::RtNone, CondType::CtNone, 0), // 0x2D
+    inst(InType::InLd, AddrMode::AmRD8, RegType::RtL, RegType::RtNone, CondType::CtNone, 0), // 0x2E
+    inst(InType::InCpl, AddrMode::AmImp, RegType::RtNone, RegType::RtNone, CondType::CtNone, 0), // 0x2F
+    inst(InType::InJr, AddrMode::AmD8, RegType::RtNone, RegType::RtNone, CondType::CtNc, 0), // 0x30
+    inst(InType::InLd, AddrMode::AmRD16, RegType::RtSp, RegType::RtNone, CondType::CtNone, 0), // 0x31
+    inst(InType::InLd, AddrMode::AmHldr, RegType::RtHl, RegType::RtA, CondType::CtNone, 0), // 0x32
+    inst(InType::InInc, AddrMode::AmR, RegType::RtSp, RegType::RtNone, CondType::CtNone, 0), // 0x33
+    inst(InType::InInc, AddrMode::AmMr, RegType::RtHl, RegType::RtNone, CondType::CtNone, 0), // 0x34
+    inst(InType::InDec, AddrMode::AmMr, RegType::RtHl, RegType::RtNone, CondType::CtNone, 0), // 0x35
+    inst(InType::InLd, AddrMode::AmMrd8, RegType::RtHl, RegType::RtNone, CondType::CtNone, 0), // 0x36
+    inst(InType::InScf, AddrMode::AmImp, RegType::RtNone, RegType::RtNone, CondType::CtNone, 0), // 0x37
+    inst(InType::InJr, AddrMode::AmD8, RegType::RtNone, RegType::RtNone, CondType::CtC, 0), // 0x38
+    inst(InType::InAdd, AddrMode::AmRr, RegType::RtHl, RegType::RtSp, CondType::CtNone, 0), // 0x39
+    inst(InType::InLd, AddrMode::AmRhld, RegType::RtA, RegType::RtHl, CondType::CtNone, 0), // 0x3A
+    inst(InType::InDec, AddrMode::AmR, RegType::RtSp, RegType::RtNone, CondType::CtNone, 0), // 0x3B
+    inst(InType::InInc, AddrMode::AmR, RegType::RtA, RegType::RtNone, CondType::CtNone, 0), // 0x3C
+    inst(InType::InDec, AddrMode::AmR, RegType::RtA, RegType::RtNone, CondType::CtNone, 0), // 0x3D
+    inst(InType::InLd, AddrMode::AmRD8, RegType::RtA, RegType::RtNone, CondType::CtNone, 0), // 0x3E
+    inst(InType::InCcf, AddrMode::AmImp, RegType::RtNone, RegType::RtNone, CondType::CtNone, 0), // 0x3F
+    inst(InType::InLd, AddrMode::AmRr, RegType::RtB, RegType::RtB, CondType::CtNone, 0), // 0x40
+    inst(InType::InLd, AddrMode::AmRr, RegType::RtB, RegType::RtC, CondType::CtNone, 0), // 0x41
+    inst(InType::InLd, AddrMode::AmRr, RegType::RtB, RegType::RtD, CondType::CtNone, 0), // 0x42
+    inst(InType::InLd, AddrMode::AmRr, RegType::RtB, RegType::RtE, CondType::CtNone, 0), // 0x43
+    inst(InType::InLd, AddrMode::AmRr, RegType::RtB, RegType::RtH, CondType::CtNone, 0), // 0x44
+    inst(InType::InLd, AddrMode::AmRr, RegType::RtB, RegType::RtL, CondType::CtNone, 0), // 0x45
+    inst(InType::InLd, AddrMode::AmRMr, RegType::RtB, RegType::RtHl, CondType::CtNone, 0), // 0x46
+    inst(InType::InLd, AddrMode::AmRr, RegType::RtB, RegType::RtA, CondType::CtNone, 0), // 0x47
+    inst(InType::InLd, AddrMode::AmRr, RegType::RtC, RegType::RtB, CondType::CtNone, 0), // 0x48
+    inst(InType::InLd, AddrMode::AmRr, RegType::RtC, RegType::RtC, CondType::CtNone, 0), // 0x49
+    inst(InType::InLd, AddrMode::AmRr, RegType::RtC, RegType::RtD, CondType::CtNone, 0), // 0x4A
+    inst(InType::InLd, AddrMode::AmRr, RegType::RtC, RegType::RtE, CondType::CtNone, 0), // 0x4B
+    inst(InType::InLd, AddrMode::AmRr, RegType::RtC, RegType::RtH, CondType::CtNone, 0), // 0x4C
+    inst(InType::InLd, AddrMode::AmRr, RegType::RtC, RegType::RtL, CondType::CtNone, 0), // 0x4D
+    inst(InType::InLd, AddrMode::AmRMr, RegType::RtC, RegType::RtHl, CondType::CtNone, 0), // 0x4E
+    inst(InType::InLd, AddrMode::AmRr, RegType::RtC, RegType::RtA, CondType::CtNone, 0), // 0x4F
+    inst(InType::InLd, AddrMode::AmRr, RegType::RtD, RegType::RtB, CondType::CtNone, 0), // 0x50
+    inst(InType::InLd, AddrMode::AmRr, RegType::RtD, RegType::RtC, CondType::CtNone, 0), // 0x51
+    inst(InType::InLd, AddrMode::AmRr, RegType::RtD, RegType::RtD, CondType::CtNone, 0), // 0x52
+    inst(InType::InLd, AddrMode::AmRr, RegType::RtD, RegType::RtE, CondType::CtNone, 0), // 0x53
+    inst(InType::InLd, AddrMode::AmRr, RegType::RtD, RegType::RtH, CondType::CtNone, 0), // 0x54
+    inst(InType::InLd, AddrMode::AmRr, RegType::RtD, RegType::RtL, CondType::CtNone, 0), // 0x55
+    inst(InType::InLd, AddrMode::AmRMr, RegType::RtD, RegType::RtHl, CondType::CtNone, 0), // 0x56
+    inst(InType::InLd, AddrMode::AmRr, RegType::RtD, RegType::RtA, CondType::CtNone, 0), // 0x57
+    inst(InType::InLd, AddrMode::AmRr, RegType::RtE, RegType::RtB, CondType::CtNone, 0), // 0x58
+    inst(InType::InLd, AddrMode::AmRr, RegType::RtE, RegType::RtC, CondType::CtNone, 0), // 0x59
+    inst(InType::InLd, AddrMode::AmRr, RegType::RtE, RegType::RtD, CondType::CtNone, 0), // 0x5A
+    inst(InType::InLd, AddrMode::AmRr, RegType::RtE, RegType::RtE, CondType::CtNone, 0), // 0x5B
+    inst(InType::InLd, AddrMode::AmRr, RegType::RtE, RegType::RtH, CondType::CtNone, 0), // 0x5C
+    inst(InType::InLd, AddrMode::AmRr, RegType::RtE, RegType::RtL, CondType::CtNone, 0), // 0x5D
+    inst(InType::InLd, AddrMode::AmRMr, RegType::RtE, RegType::RtHl, CondType::CtNone, 0), // 0x5E
+    inst(InType::InLd, AddrMode::AmRr, RegType::RtE, RegType::RtA, CondType::CtNone, 0), // 0x5F
+    inst(InType::InLd, AddrMode::AmRr, RegType::RtH, RegType::RtB, CondType::CtNone, 0), // 0x60
+    inst(InType::InLd, AddrMode::AmRr, RegType::RtH, RegType::RtC, CondType::CtNone, 0), // 0x61
+    inst(InType::InLd, AddrMode::AmRr, RegType::RtH, RegType::RtD, CondType::CtNone, 0), // 0x62
+    inst(InType::InLd, AddrMode::AmRr, RegType::RtH, RegType::RtE, CondType::CtNone, 0), // 0x63
+    inst(InType::InLd, AddrMode::AmRr, RegType::RtH, RegType::RtH, CondType::CtNone, 0), // 0x64
+    inst(InType::InLd, AddrMode::AmRr, RegType::RtH, RegType::RtL, CondType::CtNone, 0), // 0x65
+    inst(InType::InLd, AddrMode::AmRMr, RegType::RtH, RegType::RtHl, CondType::CtNone, 0), // 0x66
+    inst(InType::InLd, AddrMode::AmRr, RegType::RtH, RegType::RtA, CondType::CtNone, 0), // 0x67
+    inst(InType::InLd, AddrMode::AmRr, RegType::RtL, RegType::RtB, CondType::CtNone, 0), // 0x68
+    inst(InType::InLd, AddrMode::AmRr, RegType::RtL, RegType::RtC, CondType::CtNone, 0), // 0x69
+    inst(InType::InLd, AddrMode::AmRr, RegType::RtL, RegType::RtD, CondType::CtNone, 0), // 0x6A
+    inst(InType::InLd, AddrMode::AmRr, RegType::RtL, RegType::RtE, CondType::CtNone, 0), // 0x6B
+    inst(InType::InLd, AddrMode::AmRr, RegType::RtL, RegType::RtH, CondType::CtNone, 0), // 0x6C
+    inst(InType::InLd, AddrMode::AmRr, RegType::RtL, RegType::RtL, CondType::CtNone, 0), // 0x6D
+    inst(InType::InLd, AddrMode::AmRMr, RegType::RtL, RegType::RtHl, CondType::CtNone, 0), // 0x6E
+    inst(InType::InLd, AddrMode::AmRr, RegType::RtL, RegType::RtA, CondType::CtNone, 0), // 0x6F
+    inst(InType::InLd, AddrMode::AmMrr, RegType::RtHl, RegType::RtB, CondType::CtNone, 0), // 0x70
+    inst(InType::InLd, AddrMode::AmMrr, RegType::RtHl, RegType::RtC, CondType::CtNone, 0), // 0x71
+    inst(InType::InLd, AddrMode::AmMrr, RegType::RtHl, RegType::RtD, CondType::CtNone, 0), // 0x72
+    inst(InType::InLd, AddrMode::AmMrr, RegType::RtHl, RegType::RtE, CondType::CtNone, 0), // 0x73
+    inst(InType::InLd, AddrMode::AmMrr, RegType::RtHl, RegType::RtH, CondType::CtNone, 0), // 0x74
+    inst(InType::InLd, AddrMode::AmMrr, RegType::RtHl, RegType::RtL, CondType::CtNone, 0), // 0x75
+    inst(InType::InHalt, AddrMode::AmImp, RegType::RtNone, RegType::RtNone, CondType::CtNone, 0), // 0x76
+    inst(InType::InLd, AddrMode::AmMrr, RegType::RtHl, RegType::RtA, CondType::CtNone, 0), // 0x77
+    inst(InType::InLd, AddrMode::AmRr, RegType::RtA, RegType::RtB, CondType::CtNone, 0), // 0x78
+    inst(InType::InLd, AddrMode::AmRr, RegType::RtA, RegType::RtC, CondType::CtNone, 0), // 0x79
+    inst(InType::InLd, AddrMode::AmRr, RegType::RtA, RegType::RtD, CondType::CtNone, 0), // 0x7A
+    inst(InType::InLd, AddrMode::AmRr, RegType::RtA, RegType::RtE, CondType::CtNone, 0), // 0x7B
+    inst(InType::InLd, AddrMode::AmRr, RegType::RtA, RegType::RtH, CondType::CtNone, 0), // 0x7C
+    inst(InType::InLd, AddrMode::AmRr, RegType::RtA, RegType::RtL, CondType::CtNone, 0), // 0x7D
+    inst(InType::InLd, AddrMode::AmRMr, RegType::RtA, RegType::RtHl, CondType::CtNone, 0), // 0x7E
+    inst(InType::InLd, AddrMode::AmRr, RegType::RtA, RegType::RtA, CondType::CtNone, 0), // 0x7F
+    inst(InType::InAdd, AddrMode::AmRr, RegType::RtA, RegType::RtB, CondType::CtNone, 0), // 0x80
+    inst(InType::InAdd, AddrMode::AmRr, RegType::RtA, RegType::RtC, CondType::CtNone, 0), // 0x81
+    inst(InType::InAdd, AddrMode::AmRr, RegType::RtA, RegType::RtD, CondType::CtNone, 0), // 0x82
+    inst(InType::InAdd, AddrMode::AmRr, RegType::RtA, RegType::RtE, CondType::CtNone, 0), // 0x83
+    inst(InType::InAdd, AddrMode::AmRr, RegType::RtA, RegType::RtH, CondType::CtNone, 0), // 0x84
+    inst(InType::InAdd, AddrMode::AmRr, RegType::RtA, RegType::RtL, CondType::CtNone, 0), // 0x85
+    inst(InType::InAdd, AddrMode::AmRMr, RegType::RtA, RegType::RtHl, CondType::CtNone, 0), // 0x86
+    inst(InType::InAdd, AddrMode::AmRr, RegType::RtA, RegType::RtA, CondType::CtNone, 0), // 0x87
+    inst(InType::InAdc, AddrMode::AmRr, RegType::RtA, RegType::RtB, CondType::CtNone, 0), // 0x88
+    inst(InType::InAdc, AddrMode::AmRr, RegType::RtA, RegType::RtC, CondType::CtNone, 0), // 0x89
+    inst(InType::InAdc, AddrMode::AmRr, RegType::RtA, RegType::RtD, CondType::CtNone, 0), // 0x8A
+    inst(InType::InAdc, AddrMode::AmRr, RegType::RtA, RegType::RtE, CondType::CtNone, 0), // 0x8B
+    inst(InType::InAdc, AddrMode::AmRr, RegType::RtA, RegType::RtH, CondType::CtNone, 0), // 0x8C
+    inst(InType::InAdc, AddrMode::AmRr, RegType::RtA, RegType::RtL, CondType::CtNone, 0), // 0x8D
+    inst(InType::InAdc, AddrMode::AmRMr, RegType::RtA, RegType::RtHl, CondType::CtNone, 0), // 0x8E
+    inst(InType::InAdc, AddrMode::AmRr, RegType::RtA, RegType::RtA, CondType::CtNone, 0), // 0x8F
+    inst(InType::InSub, AddrMode::AmRr, RegType::RtA, RegType::RtB, CondType::CtNone, 0), // 0x90
+    inst(InType::InSub, AddrMode::AmRr, RegType::RtA, RegType::RtC, CondType::CtNone, 0), // 0x91
+    inst(InType::InSub, AddrMode::AmRr, RegType::RtA, RegType::RtD, CondType::CtNone, 0), // 0x92
+    inst(InType::InSub, AddrMode::AmRr, RegType::RtA, RegType::RtE, CondType::CtNone, 0), // 0x93
+    inst(InType::InSub, AddrMode::AmRr, RegType::RtA, RegType::RtH, CondType::CtNone, 0), // 0x94
+    inst(InType::InSub, AddrMode::AmRr, RegType::RtA, RegType::RtL, CondType::CtNone, 0), // 0x95
+    inst(InType::InSub, AddrMode::AmRMr, RegType::RtA, RegType::RtHl, CondType::CtNone, 0), // 0x96
+    inst(InType::InSub, AddrMode::AmRr, RegType::RtA, RegType::RtA, CondType::CtNone, 0), // 0x97
+    inst(InType::InSbc, AddrMode::AmRr, RegType::RtA, RegType::RtB, CondType::CtNone, 0), // 0x98
+    inst(InType::InSbc, AddrMode::AmRr, RegType::RtA, RegType::RtC, CondType::CtNone, 0), // 0x99
+    inst(InType::InSbc, AddrMode::AmRr, RegType::RtA, RegType::RtD, CondType::CtNone, 0), // 0x9A
+    inst(InType::InSbc, AddrMode::AmRr, RegType::RtA, RegType::RtE, CondType::CtNone, 0), // 0x9B
+    inst(InType::InSbc, AddrMode::AmRr, RegType::RtA, RegType::RtH, CondType::CtNone, 0), // 0x9C
+    inst(InType::InSbc, AddrMode::AmRr, RegType::RtA, RegType::RtL, CondType::CtNone, 0), // 0x9D
+    inst(InType::InSbc, AddrMode::AmRMr, RegType::RtA, RegType::RtHl, CondType::CtNone, 0), // 0x9E
+    inst(InType::InSbc, AddrMode::AmRr, RegType::RtA, RegType::RtA, CondType::CtNone, 0), // 0x9F
+    inst(InType::InAnd, AddrMode::AmRr, RegType::RtA, RegType::RtB, CondType::CtNone, 0), // 0xA0
+    inst(InType::InAnd, AddrMode::AmRr, RegType::RtA, RegType::RtC, CondType::CtNone, 0), // 0xA1
+    inst(InType::InAnd, AddrMode::AmRr, RegType::RtA, RegType::RtD, CondType::CtNone, 0), // 0xA2
+    inst(InType::InAnd, AddrMode::AmRr, RegType::RtA, RegType::RtE, CondType::CtNone, 0), // 0xA3
+    inst(InType::InAnd, AddrMode::AmRr, RegType::RtA, RegType::RtH, CondType::CtNone, 0), // 0xA4
+    inst(InType::InAnd, AddrMode::AmRr, RegType::RtA, RegType::RtL, CondType::CtNone, 0), // 0xA5
+    inst(InType::InAnd, AddrMode::AmRMr, RegType::RtA, RegType::RtHl, CondType::CtNone, 0), // 0xA6
+    inst(InType::InAnd, AddrMode::AmRr, RegType::RtA, RegType::RtA, CondType::CtNone, 0), // 0xA7
+    inst(InType::InXor, AddrMode::AmRr, RegType::RtA, RegType::RtB, CondType::CtNone, 0), // 0xA8
+    inst(InType::InXor, AddrMode::AmRr, RegType::RtA, RegType::RtC, CondType::CtNone, 0), // 0xA9
+    inst(InType::InXor, AddrMode::AmRr, RegType::RtA, RegType::RtD, CondType::CtNone, 0), // 0xAA
+    inst(InType::InXor, AddrMode::AmRr, RegType::RtA, RegType::RtE, CondType::CtNone, 0), // 0xAB
+    inst(InType::InXor, AddrMode::AmRr, RegType::RtA, RegType::RtH, CondType::CtNone, 0), // 0xAC
+    inst(InType::InXor, AddrMode::AmRr, RegType::RtA, RegType::RtL, CondType::CtNone, 0), // 0xAD
+    inst(InType::InXor, AddrMode::AmRMr, RegType::RtA, RegType::RtHl, CondType::CtNone, 0), // 0xAE
+    inst(InType::InXor, AddrMode::AmRr, RegType::RtA, RegType::RtA, CondType::CtNone, 0), // 0xAF
+    inst(InType::InOr, AddrMode::AmRr, RegType::RtA, RegType::RtB, CondType::CtNone, 0), // 0xB0
+    inst(InType::InOr, AddrMode::AmRr, RegType::RtA, RegType::RtC, CondType::CtNone, 0), // 0xB1
+    inst(InType::InOr, AddrMode::AmRr, RegType::RtA, RegType::RtD, CondType::CtNone, 0), // 0xB2
+    inst(InType::InOr, AddrMode::AmRr, RegType::RtA, RegType::RtE, CondType::CtNone, 0), // 0xB3
+    inst(InType::InOr, AddrMode::AmRr, RegType::RtA, RegType::RtH, CondType::CtNone, 0), // 0xB4
+    inst(InType::InOr, AddrMode::AmRr, RegType::RtA, RegType::RtL, CondType::CtNone, 0), // 0xB5
+    inst(InType::InOr, AddrMode::AmRMr, RegType::RtA, RegType::RtHl, CondType::CtNone, 0), // 0xB6
+    inst(InType::InOr, AddrMode::AmRr, RegType::RtA, RegType::RtA, CondType::CtNone, 0), // 0xB7
+    inst(InType::InCp, AddrMode::AmRr, RegType::RtA, RegType::RtB, CondType::CtNone, 0), // 0xB8
+    inst(InType::InCp, AddrMode::AmRr, RegType::RtA, RegType::RtC, CondType::CtNone, 0), // 0xB9
+    inst(InType::InCp, AddrMode::AmRr, RegType::RtA, RegType::RtD, CondType::CtNone, 0), // 0xBA
+    inst(InType::InCp, AddrMode::AmRr, RegType::RtA, RegType::RtE, CondType::CtNone, 0), // 0xBB
+    inst(InType::InCp, AddrMode::AmRr, RegType::RtA, RegType::RtH, CondType::CtNone, 0), // 0xBC
+    inst(InType::InCp, AddrMode::AmRr, RegType::RtA, RegType::RtL, CondType::CtNone, 0), // 0xBD
+    inst(InType::InCp, AddrMode::AmRMr, RegType::RtA, RegType::RtHl, CondType::CtNone, 0), // 0xBE
+    inst(InType::InCp, AddrMode::AmRr, RegType::RtA, RegType::RtA, CondType::CtNone, 0), // 0xBF
+    inst(InType::InRet, AddrMode::AmImp, RegType::RtNone, RegType::RtNone, CondType::CtNz, 0), // 0xC0
+    inst(InType::InPop, AddrMode::AmR, RegType::RtBc, RegType::RtNone, CondType::CtNone, 0), // 0xC1
+    inst(InType::InJp, AddrMode::AmD16, RegType::RtNone, RegType::RtNone, CondType::CtNz, 0), // 0xC2
+    inst(InType::InJp, AddrMode::AmD16, RegType::RtNone, RegType::RtNone, CondType::CtNone, 0), // 0xC3
+    inst(InType::InCall, AddrMode::AmD16, RegType::RtNone, RegType::RtNone, CondType::CtNz, 0), // 0xC4
+    inst(InType::InPush, AddrMode::AmR, RegType::RtBc, RegType::RtNone, CondType::CtNone, 0), // 0xC5
+    inst(InType::InAdd, AddrMode::AmRD8, RegType::RtA, RegType::RtNone, CondType::CtNone, 0), // 0xC6
+    inst(InType::InRst, AddrMode::AmImp, RegType::RtNone, RegType::RtNone, CondType::CtNone, 0x00), // 0xC7
+    inst(InType::InRet, AddrMode::AmImp, RegType::RtNone, RegType::RtNone, CondType::CtZ, 0), // 0xC8
+    inst(InType::InRet, AddrMode::AmImp, RegType::RtNone, RegType::RtNone, CondType::CtNone, 0), // 0xC9
+    inst(InType::InJp, AddrMode::AmD16, RegType::RtNone, RegType::RtNone, CondType::CtZ, 0), // 0xCA
+    inst(InType::InCb, AddrMode::AmD8, RegType::RtNone, RegType::RtNone, CondType::CtNone, 0), // 0xCB
+    inst(InType::InCall, AddrMode::AmD16, RegType::RtNone, RegType::RtNone, CondType::CtZ, 0), // 0xCC
+    inst(InType::InCall, AddrMode::AmD16, RegType::RtNone, RegType::RtNone, CondType::CtNone, 0), // 0xCD
+    inst(InType::InAdc, AddrMode::AmRD8, RegType::RtA, RegType::RtNone, CondType::CtNone, 0), // 0xCE
+    inst(InType::InRst, AddrMode::AmImp, RegType::RtNone, RegType::RtNone, CondType::CtNone, 0x08), // 0xCF
+    inst(InType::InRet, AddrMode::AmImp, RegType::RtNone, RegType::RtNone, CondType::CtNc, 0), // 0xD0
+    inst(InType::InPop, AddrMode::AmR, RegType::RtDe, RegType::RtNone, CondType::CtNone, 0), // 0xD1
+    inst(InType::InJp, AddrMode::AmD16, RegType::RtNone, RegType::RtNone, CondType::CtNc, 0), // 0xD2
+    NONE, // 0xD3
+    inst(InType::InCall, AddrMode::AmD16, RegType::RtNone, RegType::RtNone, CondType::CtNc, 0), // 0xD4
+    inst(InType::InPush, AddrMode::AmR, RegType::RtDe, RegType::RtNone, CondType::CtNone, 0), // 0xD5
+    inst(InType::InSub, AddrMode::AmRD8, RegType::RtA, RegType::RtNone, CondType::CtNone, 0), // 0xD6
+    inst(InType::InRst, AddrMode::AmImp, RegType::RtNone, RegType::RtNone, CondType::CtNone, 0x10), // 0xD7
+    inst(InType::InRet, AddrMode::AmImp, RegType::RtNone, RegType::RtNone, CondType::CtC, 0), // 0xD8
+    inst(InType::InReti, AddrMode::AmImp, RegType::RtNone, RegType::RtNone, CondType::CtNone, 0), // 0xD9
+    inst(InType::InJp, AddrMode::AmD16, RegType::RtNone, RegType::RtNone, CondType::CtC, 0), // 0xDA
+    NONE, // 0xDB
+    inst(InType::InCall, AddrMode::AmD16, RegType::RtNone, RegType::RtNone, CondType::CtC, 0), // 0xDC
+    NONE, // 0xDD
+    inst(InType::InSbc, AddrMode::AmRD8, RegType::RtA, RegType::RtNone, CondType::CtNone, 0), // 0xDE
+    inst(InType::InRst, AddrMode::AmImp, RegType::RtNone, RegType::RtNone, CondType::CtNone, 0x18), // 0xDF
+    inst(InType::InLdh, AddrMode::AmA8r, RegType::RtNone, RegType::RtA, CondType::CtNone, 0), // 0xE0
+    inst(InType::InPop, AddrMode::AmR, RegType::RtHl, RegType::RtNone, CondType::CtNone, 0), // 0xE1
+    inst(InType::InLd, AddrMode::AmMrr, RegType::RtC, RegType::RtA, CondType::CtNone, 0), // 0xE2
+    NONE, // 0xE3
+    NONE, // 0xE4
+    inst(InType::InPush, AddrMode::AmR, RegType::RtHl, RegType::RtNone, CondType::CtNone, 0), // 0xE5
+    inst(InType::InAnd, AddrMode::AmRD8, RegType::RtA, RegType::RtNone, CondType::CtNone, 0), // 0xE6
+    inst(InType::InRst, AddrMode::AmImp, RegType::RtNone, RegType::RtNone, CondType::CtNone, 0x20), // 0xE7
+    inst(InType::InAdd, AddrMode::AmRD8, RegType::RtSp, RegType::RtNone, CondType::CtNone, 0), // 0xE8
+    inst(InType::InJp, AddrMode::AmR, RegType::RtHl, RegType::RtNone, CondType::CtNone, 0), // 0xE9
+    inst(InType::InLd, AddrMode::AmA16r, RegType::RtNone, RegType::RtA, CondType::CtNone, 0), // 0xEA
+    NONE, // 0xEB
+    NONE, // 0xEC
+    NONE, // 0xED
+    inst(InType::InXor, AddrMode::AmRD8, RegType::RtA, RegType::RtNone, CondType::CtNone, 0), // 0xEE
+    inst(InType::InRst, AddrMode::AmImp, RegType::RtNone, RegType::RtNone, CondType::CtNone, 0x28), // 0xEF
+    inst(InType::InLdh, AddrMode::AmRa8, RegType::RtA, RegType::RtNone, CondType::CtNone, 0), // 0xF0
+    inst(InType::InPop, AddrMode::AmR, RegType::RtAf, RegType::RtNone, CondType::CtNone, 0), // 0xF1
+    inst(InType::InLd, AddrMode::AmRMr, RegType::RtA, RegType::RtC, CondType::CtNone, 0), // 0xF2
+    inst(InType::InDi, AddrMode::AmImp, RegType::RtNone, RegType::RtNone, CondType::CtNone, 0), // 0xF3
+    NONE, // 0xF4
+    inst(InType::InPush, AddrMode::AmR, RegType::RtAf, RegType::RtNone, CondType::CtNone, 0), // 0xF5
+    inst(InType::InOr, AddrMode::AmRD8, RegType::RtA, RegType::RtNone, CondType::CtNone, 0), // 0xF6
+    inst(InType::InRst, AddrMode::AmImp, RegType::RtNone, RegType::RtNone, CondType::CtNone, 0x30), // 0xF7
+    inst(InType::InLd, AddrMode::AmHlspr, RegType::RtHl, RegType::RtSp, CondType::CtNone, 0), // 0xF8
+    inst(InType::InLd, AddrMode::AmRr, RegType::RtSp, RegType::RtHl, CondType::CtNone, 0), // 0xF9
+    inst(InType::InLd, AddrMode::AmRa16, RegType::RtA, RegType::RtNone, CondType::CtNone, 0), // 0xFA
+    inst(InType::InEi, AddrMode::AmImp, RegType::RtNone, RegType::RtNone, CondType::CtNone, 0), // 0xFB
+    NONE, // 0xFC
+    NONE, // 0xFD
+    inst(InType::InCp, AddrMode::AmRD8, RegType::RtA, RegType::RtNone, CondType::CtNone, 0), // 0xFE
+    inst(InType::InRst, AddrMode::AmImp, RegType::RtNone, RegType::RtNone, CondType::CtNone, 0x38), // 0xFF
+];
+
+// Indexed by the byte following 0xCB.
+pub const CB_INSTRUCTIONS: [Instruction; 256] = cb_instructions();
+
+pub fn instruction_by_opcode(opcode: u8) -> &'static Instruction {
+    &INSTRUCTIONS[opcode as usize]
+}
 
-        // 0x2X
-        0x20 => Some(Instruction {
-            type_in: InType::InJr,
-            mode: AddrMode::AmD8,
-            reg_1: RegType::RtNone,
-            reg_2: RegType::RtNone,
-            cond: CondType::CtNz,
-            param: 0,
-        }),
-        0x21 => Some(Instruction {
-            type_in: InType::InLd,
-            mode: AddrMode::AmRD16,
-            reg_1: RegType::RtHl,
-            reg_2: RegType::RtNone,
-            cond: CondType::CtNone,
-            param: 0,
-        }),
-        0x22 => Some(Instruction {
-            type_in: InType::InLd,
-            mode: AddrMode::AmHlir,
-            reg_1: RegType::RtHl,
-            reg_2: RegType::RtA,
-            cond: CondType::CtNone,
-            param: 0,
-        }),
-        0x23 => Some(Instruction {
-            type_in: InType::InInc,
-            mode: AddrMode::AmR,
-            reg_1: RegType::RtHl,
-            reg_2: RegType::RtNone,
-            cond: CondType::CtNone,
-            param: 0,
-        }),
-        0x24 => Some(Instruction {
-            type_in: InType::InInc,
-            mode: AddrMode::AmR,
-            reg_1: RegType::RtH,
-            reg_2: RegType::RtNone,
-            cond: CondType::CtNone,
-            param: 0,
-        }),
-        0x25 => Some(Instruction {
-            type_in: InType::InDec,
-            mode: AddrMode::AmR,
-            reg_1: RegType::RtH,
-            reg_2: RegType::RtNone,
-            cond: CondType::CtNone,
-            param: 0,
-        }),
-        0x26 => Some(Instruction {
-            type_in: InType::InLd,
-            mode: AddrMode::AmRD8,
-            reg_1: RegType::RtH,
-            reg_2: RegType::RtNone,
-            cond: CondType::CtNone,
-            param: 0,
-        }),
-        0x28 => Some(Instruction {
-            type_in: InType::InJr,
-            mode: AddrMode::AmD8,
-            reg_1: RegType::RtNone,
-            reg_2: RegType::RtNone,
-            cond: CondType::CtZ,
-            param: 0,
-        }),
-        0x29 => Some(Instruction {
-            type_in: InType::InAdd,
-            mode: AddrMode::AmRr,
-            reg_1: RegType::RtHl,
-            reg_2: RegType::RtHl,
-            cond: CondType::CtNone,
-            param: 0,
-        }),
-        0x2A => Some(Instruction {
-            type_in: InType::InLd,
-            mode: AddrMode::AmRhli,
-            reg_1: RegType::RtA,
-            reg_2: RegType::RtHl,
-            cond: CondType::CtNone,
-            param: 0,
-        }),
-        0x2B => Some(Instruction {
-            type_in: InType::InDec,
-            mode: AddrMode::AmR,
-            reg_1: RegType::RtHl,
-            reg_2: RegType::RtNone,
-            cond: CondType::CtNone,
-            param: 0,
-        }),
-        0x2C => Some(Instruction {
-            type_in: InType::InInc,
-            mode: AddrMode::AmR,
-            reg_1: RegType::RtL,
-            reg_2: RegType::RtNone,
-            cond: CondType::CtNone,
-            param: 0,
-        }),
-        0x2D => Some(Instruction {
-            type_in: InType::InDec,
-            mode: AddrMode::AmR,
-            reg_1: RegType::RtL,
-            reg_2: RegType::RtNone,
-            cond: CondType::CtNone,
-            param: 0,
-        }),
-        0x2E => Some(Instruction {
-            type_in: InType::InLd,
-            mode: AddrMode::AmRD8,
-            reg_1: RegType::RtL,
-            reg_2: RegType::RtNone,
-            cond: CondType::CtNone,
-            param: 0,
-        }),
+pub fn cb_instruction_by_opcode(op: u8) -> &'static Instruction {
+    &CB_INSTRUCTIONS[op as usize]
+}
 
-        // 0x3X
-        0x30 => Some(Instruction {
-            type_in: InType::InJr,
-            mode: AddrMode::AmD8,
-            reg_1: RegType::RtNone,
-            reg_2: RegType::RtNone,
-            cond: CondType::CtNc,
-            param: 0,
-        }),
-        0x31 => Some(Instruction {
-            type_in: InType::InLd,
-            mode: AddrMode::AmRD16,
-            reg_1: RegType::RtSp,
-            reg_2: RegType::RtNone,
-            cond: CondType::CtNone,
-            param: 0,
-        }),
-        0x32 => Some(Instruction {
-            type_in: InType::InLd,
-            mode: AddrMode::AmHldr,
-            reg_1: RegType::RtHl,
-            reg_2: RegType::RtA,
-            cond: CondType::CtNone,
-            param: 0,
-        }),
-        0x33 => Some(Instruction {
-            type_in: InType::InInc,
-            mode: AddrMode::AmR,
-            reg_1: RegType::RtSp,
-            reg_2: RegType::RtNone,
-            cond: CondType::CtNone,
-            param: 0,
-        }),
-        0x34 => Some(Instruction {
-            type_in: InType::InInc,
-            mode: AddrMode::AmMr,
-            reg_1: RegType::RtHl,
-            reg_2: RegType::RtNone,
-            cond: CondType::CtNone,
-            param: 0,
-        }),
-        0x35 => Some(Instruction {
-            type_in: InType::InDec,
-            mode: AddrMode::AmMr,
-            reg_1: RegType::RtHl,
-            reg_2: RegType::RtNone,
-            cond: CondType::CtNone,
-            param: 0,
-        }),
-        0x36 => Some(Instruction {
-            type_in: InType::InLd,
-            mode: AddrMode::AmMrd8,
-            reg_1: RegType::RtHl,
-            reg_2: RegType::RtNone,
-            cond: CondType::CtNone,
-            param: 0,
-        }),
-        0x38 => Some(Instruction {
-            type_in: InType::InJr,
-            mode: AddrMode::AmD8,
-            reg_1: RegType::RtNone,
-            reg_2: RegType::RtNone,
-            cond: CondType::CtC,
-            param: 0,
-        }),
-        0x39 => Some(Instruction {
-            type_in: InType::InAdd,
-            mode: AddrMode::AmRr,
-            reg_1: RegType::RtHl,
-            reg_2: RegType::RtSp,
-            cond: CondType::CtNone,
-            param: 0,
-        }),
-        0x3A => Some(Instruction {
-            type_in: InType::InLd,
-            mode: AddrMode::AmRhld,
-            reg_1: RegType::RtA,
-            reg_2: RegType::RtHl,
-            cond: CondType::CtNone,
-            param: 0,
-        }),
-        0x3B => Some(Instruction {
-            type_in: InType::InDec,
-            mode: AddrMode::AmR,
-            reg_1: RegType::RtSp,
-            reg_2: RegType::RtNone,
-            cond: CondType::CtNone,
-            param: 0,
-        }),
-        0x3C => Some(Instruction {
-            type_in: InType::InInc,
-            mode: AddrMode::AmR,
-            reg_1: RegType::RtA,
-            reg_2: RegType::RtNone,
-            cond: CondType::CtNone,
-            param: 0,
-        }),
-        0x3D => Some(Instruction {
-            type_in: InType::InDec,
-            mode: AddrMode::AmR,
-            reg_1: RegType::RtA,
-            reg_2: RegType::RtNone,
-            cond: CondType::CtNone,
-            param: 0,
-        }),
-        0x3E => Some(Instruction {
-            type_in: InType::InLd,
-            mode: AddrMode::AmRD8,
-            reg_1: RegType::RtA,
-            reg_2: RegType::RtNone,
-            cond: CondType::CtNone,
-            param: 0,
-        }),
+// CB-prefixed opcodes are laid out as `oo bbb rrr`: the operation group in
+// bits 6-7, the bit index (or rotate/shift kind for group 0) in bits 3-5 and
+// the register operand in bits 0-2.
+const CB_REG_LOOKUP: [RegType; 8] = [
+    RegType::RtB,
+    RegType::RtC,
+    RegType::RtD,
+    RegType::RtE,
+    RegType::RtH,
+    RegType::RtL,
+    RegType::RtHl,
+    RegType::RtA,
+];
 
-        // 0x4X
-        0x40 => Some(Instruction {
-            type_in: InType::InLd,
-            mode: AddrMode::AmRr,
-            reg_1: RegType::RtB,
-            reg_2: RegType::RtB,
-            cond: CondType::CtNone,
-            param: 0,
-        }),
-        0x41 => Some(Instruction {
-            type_in: InType::InLd,
-            mode: AddrMode::AmRr,
-            reg_1: RegType::RtB,
-            reg_2: RegType::RtC,
-            cond: CondType::CtNone,
-            param: 0,
-        }),
-        0x42 => Some(Instruction {
-            type_in: InType::InLd,
-            mode: AddrMode::AmRr,
-            reg_1: RegType::RtB,
-            reg_2: RegType::RtD,
-            cond: CondType::CtNone,
-            param: 0,
-        }),
-        0x43 => Some(Instruction {
-            type_in: InType::InLd,
-            mode: AddrMode::AmRr,
-            reg_1: RegType::RtB,
-            reg_2: RegType::RtE,
-            cond: CondType::CtNone,
-            param: 0,
-        }),
-        0x44 => Some(Instruction {
-            type_in: InType::InLd,
-            mode: AddrMode::AmRr,
-            reg_1: RegType::RtB,
-            reg_2: RegType::RtH,
-            cond: CondType::CtNone,
-            param: 0,
-        }),
-        0x45 => Some(Instruction {
-            type_in: InType::InLd,
-            mode: AddrMode::AmRr,
-            reg_1: RegType::RtB,
-            reg_2: RegType::RtL,
-            cond: CondType::CtNone,
-            param: 0,
-        }),
-        0x46 => Some(Instruction {
-            type_in: InType::InLd,
-            mode: AddrMode::AmRMr,
-            reg_1: RegType::RtB,
-            reg_2: RegType::RtHl,
-            cond: CondType::CtNone,
-            param: 0,
-        }),
-        0x47 => Some(Instruction {
-            type_in: InType::InLd,
-            mode: AddrMode::AmRr,
-            reg_1: RegType::RtB,
-            reg_2: RegType::RtA,
-            cond: CondType::CtNone,
-            param: 0,
-        }),
-        0x48 => Some(Instruction {
-            type_in: InType::InLd,
-            mode: AddrMode::AmRr,
-            reg_1: RegType::RtC,
-            reg_2: RegType::RtB,
-            cond: CondType::CtNone,
-            param: 0,
-        }),
-        0x49 => Some(Instruction {
-            type_in: InType::InLd,
-            mode: AddrMode::AmRr,
-            reg_1: RegType::RtC,
-            reg_2: RegType::RtC,
-            cond: CondType::CtNone,
-            param: 0,
-        }),
-        0x4A => Some(Instruction {
-            type_in: InType::InLd,
-            mode: AddrMode::AmRr,
-            reg_1: RegType::RtC,
-            reg_2: RegType::RtD,
-            cond: CondType::CtNone,
-            param: 0,
-        }),
-        0x4B => Some(Instruction {
-            type_in: InType::InLd,
-            mode: AddrMode::AmRr,
-            reg_1: RegType::RtC,
-            reg_2: RegType::RtE,
-            cond: CondType::CtNone,
-            param: 0,
-        }),
-        0x4C => Some(Instruction {
-            type_in: InType::InLd,
-            mode: AddrMode::AmRr,
-            reg_1: RegType::RtC,
-            reg_2: RegType::RtH,
-            cond: CondType::CtNone,
-            param: 0,
-        }),
-        0x4D => Some(Instruction {
-            type_in: InType::InLd,
-            mode: AddrMode::AmRr,
-            reg_1: RegType::RtC,
-            reg_2: RegType::RtL,
-            cond: CondType::CtNone,
-            param: 0,
-        }),
-        0x4E => Some(Instruction {
-            type_in: InType::InLd,
-            mode: AddrMode::AmRMr,
-            reg_1: RegType::RtC,
-            reg_2: RegType::RtHl,
-            cond: CondType::CtNone,
-            param: 0,
-        }),
-        0x4F => Some(Instruction {
-            type_in: InType::InLd,
-            mode: AddrMode::AmRr,
-            reg_1: RegType::RtC,
-            reg_2: RegType::RtA,
-            cond: CondType::CtNone,
-            param: 0,
-        }),
-        0x50 => Some(Instruction {
-            type_in: InType::InLd,
-            mode: AddrMode::AmRr,
-            reg_1: RegType::RtD,
-            reg_2: RegType::RtB,
-            cond: CondType::CtNone,
-            param: 0,
-        }),
-        0x51 => Some(Instruction {
-            type_in: InType::InLd,
-            mode: AddrMode::AmRr,
-            reg_1: RegType::RtD,
-            reg_2: RegType::RtC,
-            cond: CondType::CtNone,
-            param: 0,
-        }),
-        0x52 => Some(Instruction {
-            type_in: InType::InLd,
-            mode: AddrMode::AmRr,
-            reg_1: RegType::RtD,
-            reg_2: RegType::RtD,
-            cond: CondType::CtNone,
-            param: 0,
-        }),
-        0x53 => Some(Instruction {
-            type_in: InType::InLd,
-            mode: AddrMode::AmRr,
-            reg_1: RegType::RtD,
-            reg_2: RegType::RtE,
-            cond: CondType::CtNone,
-            param: 0,
-        }),
-        0x54 => Some(Instruction {
-            type_in: InType::InLd,
-            mode: AddrMode::AmRr,
-            reg_1: RegType::RtD,
-            reg_2: RegType::RtH,
-            cond: CondType::CtNone,
-            param: 0,
-        }),
-        0x55 => Some(Instruction {
-            type_in: InType::InLd,
-            mode: AddrMode::AmRr,
-            reg_1: RegType::RtD,
-            reg_2: RegType::RtL,
-            cond: CondType::CtNone,
-            param: 0,
-        }),
-        0x56 => Some(Instruction {
-            type_in: InType::InLd,
-            mode: AddrMode::AmRMr,
-            reg_1: RegType::RtD,
-            reg_2: RegType::RtHl,
-            cond: CondType::CtNone,
-            param: 0,
-        }),
-        0x57 => Some(Instruction {
-            type_in: InType::InLd,
-            mode: AddrMode::AmRr,
-            reg_1: RegType::RtD,
-            reg_2: RegType::RtA,
-            cond: CondType::CtNone,
-            param: 0,
-        }),
-        0x58 => Some(Instruction {
-            type_in: InType::InLd,
-            mode: AddrMode::AmRr,
-            reg_1: RegType::RtE,
-            reg_2: RegType::RtB,
-            cond: CondType::CtNone,
-            param: 0,
-        }),
-        0x59 => Some(Instruction {
-            type_in: InType::InLd,
-            mode: AddrMode::AmRr,
-            reg_1: RegType::RtE,
-            reg_2: RegType::RtC,
-            cond: CondType::CtNone,
-            param: 0,
-        }),
-        0x5A => Some(Instruction {
-            type_in: InType::InLd,
-            mode: AddrMode::AmRr,
-            reg_1: RegType::RtE,
-            reg_2: RegType::RtD,
-            cond: CondType::CtNone,
-            param: 0,
-        }),
-        0x5B => Some(Instruction {
-            type_in: InType::InLd,
-            mode: AddrMode::AmRr,
-            reg_1: RegType::RtE,
-            reg_2: RegType::RtE,
-            cond: CondType::CtNone,
-            param: 0,
-        }),
-        0x5C => Some(Instruction {
-            type_in: InType::InLd,
-            mode: AddrMode::AmRr,
-            reg_1: RegType::RtE,
-            reg_2: RegType::RtH,
-            cond: CondType::CtNone,
-            param: 0,
-        }),
-        0x5D => Some(Instruction {
-            type_in: InType::InLd,
-            mode: AddrMode::AmRr,
-            reg_1: RegType::RtE,
-            reg_2: RegType::RtL,
-            cond: CondType::CtNone,
-            param: 0,
-        }),
-        0x5E => Some(Instruction {
-            type_in: InType::InLd,
-            mode: AddrMode::AmRMr,
-            reg_1: RegType::RtE,
-            reg_2: RegType::RtHl,
-            cond: CondType::CtNone,
-            param: 0,
-        }),
-        0x5F => Some(Instruction {
-            type_in: InType::InLd,
-            mode: AddrMode::AmRr,
-            reg_1: RegType::RtE,
-            reg_2: RegType::RtA,
-            cond: CondType::CtNone,
-            param: 0,
-        }),
+pub const fn cb_decode_reg(op: u8) -> RegType {
+    CB_REG_LOOKUP[(op & 0b111) as usize]
+}
 
-        // 0x6X
-        0x60 => Some(Instruction {
-            type_in: InType::InLd,
-            mode: AddrMode::AmRr,
-            reg_1: RegType::RtH,
-            reg_2: RegType::RtB,
-            cond: CondType::CtNone,
-            param: 0,
-        }),
-        0x61 => Some(Instruction {
-            type_in: InType::InLd,
-            mode: AddrMode::AmRr,
-            reg_1: RegType::RtH,
-            reg_2: RegType::RtC,
-            cond: CondType::CtNone,
-            param: 0,
-        }),
-        0x62 => Some(Instruction {
-            type_in: InType::InLd,
-            mode: AddrMode::AmRr,
-            reg_1: RegType::RtH,
-            reg_2: RegType::RtD,
-            cond: CondType::CtNone,
-            param: 0,
-        }),
-        0x63 => Some(Instruction {
-            type_in: InType::InLd,
-            mode: AddrMode::AmRr,
-            reg_1: RegType::RtH,
-            reg_2: RegType::RtE,
-            cond: CondType::CtNone,
-            param: 0,
-        }),
-        0x64 => Some(Instruction {
-            type_in: InType::InLd,
-            mode: AddrMode::AmRr,
-            reg_1: RegType::RtH,
-            reg_2: RegType::RtH,
-            cond: CondType::CtNone,
-            param: 0,
-        }),
-        0x65 => Some(Instruction {
-            type_in: InType::InLd,
-            mode: AddrMode::AmRr,
-            reg_1: RegType::RtH,
-            reg_2: RegType::RtL,
-            cond: CondType::CtNone,
-            param: 0,
-        }),
-        0x66 => Some(Instruction {
-            type_in: InType::InLd,
-            mode: AddrMode::AmRMr,
-            reg_1: RegType::RtH,
-            reg_2: RegType::RtHl,
-            cond: CondType::CtNone,
-            param: 0,
-        }),
-        0x67 => Some(Instruction {
-            type_in: InType::InLd,
-            mode: AddrMode::AmRr,
-            reg_1: RegType::RtH,
-            reg_2: RegType::RtA,
-            cond: CondType::CtNone,
-            param: 0,
-        }),
-        0x68 => Some(Instruction {
-            type_in: InType::InLd,
-            mode: AddrMode::AmRr,
-            reg_1: RegType::RtL,
-            reg_2: RegType::RtB,
-            cond: CondType::CtNone,
-            param: 0,
-        }),
-        0x69 => Some(Instruction {
-            type_in: InType::InLd,
-            mode: AddrMode::AmRr,
-            reg_1: RegType::RtL,
-            reg_2: RegType::RtC,
-            cond: CondType::CtNone,
-            param: 0,
-        }),
-        0x6A => Some(Instruction {
-            type_in: InType::InLd,
-            mode: AddrMode::AmRr,
-            reg_1: RegType::RtL,
-            reg_2: RegType::RtD,
-            cond: CondType::CtNone,
-            param: 0,
-        }),
-        0x6B => Some(Instruction {
-            type_in: InType::InLd,
-            mode: AddrMode::AmRr,
-            reg_1: RegType::RtL,
-            reg_2: RegType::RtE,
-            cond: CondType::CtNone,
-            param: 0,
-        }),
-        0x6C => Some(Instruction {
-            type_in: InType::InLd,
-            mode: AddrMode::AmRr,
-            reg_1: RegType::RtL,
-            reg_2: RegType::RtH,
-            cond: CondType::CtNone,
-            param: 0,
-        }),
-        0x6D => Some(Instruction {
-            type_in: InType::InLd,
-            mode: AddrMode::AmRr,
-            reg_1: RegType::RtL,
-            reg_2: RegType::RtL,
-            cond: CondType::CtNone,
-            param: 0,
-        }),
-        0x6E => Some(Instruction {
-            type_in: InType::InLd,
-            mode: AddrMode::AmRMr,
-            reg_1: RegType::RtL,
-            reg_2: RegType::RtHl,
-            cond: CondType::CtNone,
-            param: 0,
-        }),
-        0x6F => Some(Instruction {
-            type_in: InType::InLd,
-            mode: AddrMode::AmRr,
-            reg_1: RegType::RtL,
-            reg_2: RegType::RtA,
-            cond: CondType::CtNone,
-            param: 0,
-        }),
-        0x70 => Some(Instruction {
-            type_in: InType::InLd,
-            mode: AddrMode::AmMrr,
-            reg_1: RegType::RtHl,
-            reg_2: RegType::RtB,
-            cond: CondType::CtNone,
-            param: 0,
-        }),
-        0x71 => Some(Instruction {
-            type_in: InType::InLd,
-            mode: AddrMode::AmMrr,
-            reg_1: RegType::RtHl,
-            reg_2: RegType::RtC,
-            cond: CondType::CtNone,
-            param: 0,
-        }),
-        0x72 => Some(Instruction {
-            type_in: InType::InLd,
-            mode: AddrMode::AmMrr,
-            reg_1: RegType::RtHl,
-            reg_2: RegType::RtD,
-            cond: CondType::CtNone,
-            param: 0,
-        }),
-        0x73 => Some(Instruction {
-            type_in: InType::InLd,
-            mode: AddrMode::AmMrr,
-            reg_1: RegType::RtHl,
-            reg_2: RegType::RtE,
-            cond: CondType::CtNone,
-            param: 0,
-        }),
-        0x74 => Some(Instruction {
-            type_in: InType::InLd,
-            mode: AddrMode::AmMrr,
-            reg_1: RegType::RtHl,
-            reg_2: RegType::RtH,
-            cond: CondType::CtNone,
-            param: 0,
-        }),
-        0x75 => Some(Instruction {
-            type_in: InType::InLd,
-            mode: AddrMode::AmMrr,
-            reg_1: RegType::RtHl,
-            reg_2: RegType::RtL,
-            cond: CondType::CtNone,
-            param: 0,
-        }),
-        0x76 => Some(Instruction {
-            type_in: InType::InHalt,
-            mode: AddrMode::AmImp,
-            reg_1: RegType::RtNone,
-            reg_2: RegType::RtNone,
-            cond: CondType::CtNone,
-            param: 0,
-        }),
-        0x77 => Some(Instruction {
-            type_in: InType::InLd,
-            mode: AddrMode::AmMrr,
-            reg_1: RegType::RtHl,
-            reg_2: RegType::RtA,
-            cond: CondType::CtNone,
-            param: 0,
-        }),
-        0x78 => Some(Instruction {
-            type_in: InType::InLd,
-            mode: AddrMode::AmRr,
-            reg_1: RegType::RtA,
-            reg_2: RegType::RtB,
-            cond: CondType::CtNone,
-            param: 0,
-        }),
-        0x79 => Some(Instruction {
-            type_in: InType::InLd,
-            mode: AddrMode::AmRr,
-            reg_1: RegType::RtA,
-            reg_2: RegType::RtC,
-            cond: CondType::CtNone,
-            param: 0,
-        }),
-        0x7A => Some(Instruction {
-            type_in: InType::InLd,
-            mode: AddrMode::AmRr,
-            reg_1: RegType::RtA,
-            reg_2: RegType::RtD,
-            cond: CondType::CtNone,
-            param: 0,
-        }),
-        0x7B => Some(Instruction {
-            type_in: InType::InLd,
-            mode: AddrMode::AmRr,
-            reg_1: RegType::RtA,
-            reg_2: RegType::RtE,
-            cond: CondType::CtNone,
-            param: 0,
-        }),
-        0x7C => Some(Instruction {
-            type_in: InType::InLd,
-            mode: AddrMode::AmRr,
-            reg_1: RegType::RtA,
-            reg_2: RegType::RtH,
-            cond: CondType::CtNone,
-            param: 0,
-        }),
-        0x7D => Some(Instruction {
-            type_in: InType::InLd,
-            mode: AddrMode::AmRr,
-            reg_1: RegType::RtA,
-            reg_2: RegType::RtL,
-            cond: CondType::CtNone,
-            param: 0,
-        }),
-        0x7E => Some(Instruction {
-            type_in: InType::InLd,
-            mode: AddrMode::AmRMr,
-            reg_1: RegType::RtA,
-            reg_2: RegType::RtHl,
-            cond: CondType::CtNone,
-            param: 0,
-        }),
-        0x7F => Some(Instruction {
-            type_in: InType::InLd,
-            mode: AddrMode::AmRr,
-            reg_1: RegType::RtA,
-            reg_2: RegType::RtA,
-            cond: CondType::CtNone,
-            param: 0,
-        }),
-        0x80 => Some(Instruction {
-            type_in: InType::InAdd,
-            mode: AddrMode::AmRr,
-            reg_1: RegType::RtA,
-            reg_2: RegType::RtB,
-            cond: CondType::CtNone,
-            param: 0,
-        }),
-        0x81 => Some(Instruction {
-            type_in: InType::InAdd,
-            mode: AddrMode::AmRr,
-            reg_1: RegType::RtA,
-            reg_2: RegType::RtC,
-            cond: CondType::CtNone,
-            param: 0,
-        }),
-        0x82 => Some(Instruction {
-            type_in: InType::InAdd,
-            mode: AddrMode::AmRr,
-            reg_1: RegType::RtA,
-            reg_2: RegType::RtD,
-            cond: CondType::CtNone,
-            param: 0,
-        }),
-        0x83 => Some(Instruction {
-            type_in: InType::InAdd,
-            mode: AddrMode::AmRr,
-            reg_1: RegType::RtA,
-            reg_2: RegType::RtE,
-            cond: CondType::CtNone,
-            param: 0,
-        }),
-        0x84 => Some(Instruction {
-            type_in: InType::InAdd,
-            mode: AddrMode::AmRr,
-            reg_1: RegType::RtA,
-            reg_2: RegType::RtH,
-            cond: CondType::CtNone,
-            param: 0,
-        }),
-        0x85 => Some(Instruction {
-            type_in: InType::InAdd,
-            mode: AddrMode::AmRr,
-            reg_1: RegType::RtA,
-            reg_2: RegType::RtL,
-            cond: CondType::CtNone,
-            param: 0,
-        }),
-        0x86 => Some(Instruction {
-            type_in: InType::InAdd,
-            mode: AddrMode::AmRMr,
-            reg_1: RegType::RtA,
-            reg_2: RegType::RtHl,
-            cond: CondType::CtNone,
-            param: 0,
-        }),
-        0x87 => Some(Instruction {
-            type_in: InType::InAdd,
-            mode: AddrMode::AmRr,
-            reg_1: RegType::RtA,
-            reg_2: RegType::RtA,
-            cond: CondType::CtNone,
-            param: 0,
-        }),
-        0x88 => Some(Instruction {
-            type_in: InType::InAdc,
-            mode: AddrMode::AmRr,
-            reg_1: RegType::RtA,
-            reg_2: RegType::RtB,
-            cond: CondType::CtNone,
-            param: 0,
-        }),
-        0x89 => Some(Instruction {
-            type_in: InType::InAdc,
-            mode: AddrMode::AmRr,
-            reg_1: RegType::RtA,
-            reg_2: RegType::RtC,
-            cond: CondType::CtNone,
-            param: 0,
-        }),
-        0x8A => Some(Instruction {
-            type_in: InType::InAdc,
-            mode: AddrMode::AmRr,
-            reg_1: RegType::RtA,
-            reg_2: RegType::RtD,
-            cond: CondType::CtNone,
-            param: 0,
-        }),
-        0x8B => Some(Instruction {
-            type_in: InType::InAdc,
-            mode: AddrMode::AmRr,
-            reg_1: RegType::RtA,
-            reg_2: RegType::RtE,
-            cond: CondType::CtNone,
-            param: 0,
-        }),
-        0x8C => Some(Instruction {
-            type_in: InType::InAdc,
-            mode: AddrMode::AmRr,
-            reg_1: RegType::RtA,
-            reg_2: RegType::RtH,
-            cond: CondType::CtNone,
-            param: 0,
-        }),
-        0x8D => Some(Instruction {
-            type_in: InType::InAdc,
-            mode: AddrMode::AmRr,
-            reg_1: RegType::RtA,
-            reg_2: RegType::RtL,
-            cond: CondType::CtNone,
-            param: 0,
-        }),
-        0x8E => Some(Instruction {
-            type_in: InType::InAdc,
-            mode: AddrMode::AmRMr,
-            reg_1: RegType::RtA,
-            reg_2: RegType::RtHl,
-            cond: CondType::CtNone,
-            param: 0,
-        }),
-        0x8F => Some(Instruction {
-            type_in: InType::InAdc,
-            mode: AddrMode::AmRr,
-            reg_1: RegType::RtA,
-            reg_2: RegType::RtA,
-            cond: CondType::CtNone,
-            param: 0,
-        }),
-        0x90 => Some(Instruction {
-            type_in: InType::InSub,
-            mode: AddrMode::AmRr,
-            reg_1: RegType::RtA,
-            reg_2: RegType::RtB,
-            cond: CondType::CtNone,
-            param: 0,
-        }),
-        0x91 => Some(Instruction {
-            type_in: InType::InSub,
-            mode: AddrMode::AmRr,
-            reg_1: RegType::RtA,
-            reg_2: RegType::RtC,
-            cond: CondType::CtNone,
-            param: 0,
-        }),
-        0x92 => Some(Instruction {
-            type_in: InType::InSub,
-            mode: AddrMode::AmRr,
-            reg_1: RegType::RtA,
-            reg_2: RegType::RtD,
-            cond: CondType::CtNone,
-            param: 0,
-        }),
-        0x93 => Some(Instruction {
-            type_in: InType::InSub,
-            mode: AddrMode::AmRr,
-            reg_1: RegType::RtA,
-            reg_2: RegType::RtE,
-            cond: CondType::CtNone,
-            param: 0,
-        }),
-        0x94 => Some(Instruction {
-            type_in: InType::InSub,
-            mode: AddrMode::AmRr,
-            reg_1: RegType::RtA,
-            reg_2: RegType::RtH,
-            cond: CondType::CtNone,
-            param: 0,
-        }),
-        0x95 => Some(Instruction {
-            type_in: InType::InSub,
-            mode: AddrMode::AmRr,
-            reg_1: RegType::RtA,
-            reg_2: RegType::RtL,
-            cond: CondType::CtNone,
-            param: 0,
-        }),
-        0x96 => Some(Instruction {
-            type_in: InType::InSub,
-            mode: AddrMode::AmRMr,
-            reg_1: RegType::RtA,
-            reg_2: RegType::RtHl,
-            cond: CondType::CtNone,
-            param: 0,
-        }),
-        0x97 => Some(Instruction {
-            type_in: InType::InSub,
-            mode: AddrMode::AmRr,
-            reg_1: RegType::RtA,
-            reg_2: RegType::RtA,
-            cond: CondType::CtNone,
-            param: 0,
-        }),
-        0x98 => Some(Instruction {
-            type_in: InType::InSbc,
-            mode: AddrMode::AmRr,
-            reg_1: RegType::RtA,
-            reg_2: RegType::RtB,
-            cond: CondType::CtNone,
-            param: 0,
-        }),
-        0x99 => Some(Instruction {
-            type_in: InType::InSbc,
-            mode: AddrMode::AmRr,
-            reg_1: RegType::RtA,
-            reg_2: RegType::RtC,
-            cond: CondType::CtNone,
-            param: 0,
-        }),
-        0x9A => Some(Instruction {
-            type_in: InType::InSbc,
-            mode: AddrMode::AmRr,
-            reg_1: RegType::RtA,
-            reg_2: RegType::RtD,
-            cond: CondType::CtNone,
-            param: 0,
-        }),
-        0x9B => Some(Instruction {
-            type_in: InType::InSbc,
-            mode: AddrMode::AmRr,
-            reg_1: RegType::RtA,
-            reg_2: RegType::RtE,
-            cond: CondType::CtNone,
-            param: 0,
-        }),
-        0x9C => Some(Instruction {
-            type_in: InType::InSbc,
-            mode: AddrMode::AmRr,
-            reg_1: RegType::RtA,
-            reg_2: RegType::RtH,
-            cond: CondType::CtNone,
-            param: 0,
-        }),
-        0x9D => Some(Instruction {
-            type_in: InType::InSbc,
-            mode: AddrMode::AmRr,
-            reg_1: RegType::RtA,
-            reg_2: RegType::RtL,
-            cond: CondType::CtNone,
-            param: 0,
-        }),
-        0x9E => Some(Instruction {
-            type_in: InType::InSbc,
-            mode: AddrMode::AmRMr,
-            reg_1: RegType::RtA,
-            reg_2: RegType::RtHl,
-            cond: CondType::CtNone,
-            param: 0,
-        }),
-        0x9F => Some(Instruction {
-            type_in: InType::InSbc,
-            mode: AddrMode::AmRr,
-            reg_1: RegType::RtA,
-            reg_2: RegType::RtA,
-            cond: CondType::CtNone,
-            param: 0,
-        }),
-        0xA0 => Some(Instruction {
-            type_in: InType::InAnd,
-            mode: AddrMode::AmRr,
-            reg_1: RegType::RtA,
-            reg_2: RegType::RtB,
-            cond: CondType::CtNone,
-            param: 0,
-        }),
-        0xA1 => Some(Instruction {
-            type_in: InType::InAnd,
-            mode: AddrMode::AmRr,
-            reg_1: RegType::RtA,
-            reg_2: RegType::RtC,
-            cond: CondType::CtNone,
-            param: 0,
-        }),
-        0xA2 => Some(Instruction {
-            type_in: InType::InAnd,
-            mode: AddrMode::AmRr,
-            reg_1: RegType::RtA,
-            reg_2: RegType::RtD,
-            cond: CondType::CtNone,
-            param: 0,
-        }),
-        0xA3 => Some(Instruction {
-            type_in: InType::InAnd,
-            mode: AddrMode::AmRr,
-            reg_1: RegType::RtA,
-            reg_2: RegType::RtE,
-            cond: CondType::CtNone,
-            param: 0,
-        }),
-        0xA4 => Some(Instruction {
-            type_in: InType::InAnd,
-            mode: AddrMode::AmRr,
-            reg_1: RegType::RtA,
-            reg_2: RegType::RtH,
-            cond: CondType::CtNone,
-            param: 0,
-        }),
-        0xA5 => Some(Instruction {
-            type_in: InType::InAnd,
-            mode: AddrMode::AmRr,
-            reg_1: RegType::RtA,
-            reg_2: RegType::RtL,
-            cond: CondType::CtNone,
-            param: 0,
-        }),
-        0xA6 => Some(Instruction {
-            type_in: InType::InAnd,
-            mode: AddrMode::AmRMr,
-            reg_1: RegType::RtA,
-            reg_2: RegType::RtHl,
-            cond: CondType::CtNone,
-            param: 0,
-        }),
-        0xA7 => Some(Instruction {
-            type_in: InType::InAnd,
-            mode: AddrMode::AmRr,
-            reg_1: RegType::RtA,
-            reg_2: RegType::RtA,
-            cond: CondType::CtNone,
-            param: 0,
-        }),
-        0xA8 => Some(Instruction {
-            type_in: InType::InXor,
-            mode: AddrMode::AmRr,
-            reg_1: RegType::RtA,
-            reg_2: RegType::RtB,
-            cond: CondType::CtNone,
-            param: 0,
-        }),
-        0xA9 => Some(Instruction {
-            type_in: InType::InXor,
-            mode: AddrMode::AmRr,
-            reg_1: RegType::RtA,
-            reg_2: RegType::RtC,
-            cond: CondType::CtNone,
-            param: 0,
-        }),
-        0xAA => Some(Instruction {
-            type_in: InType::InXor,
-            mode: AddrMode::AmRr,
-            reg_1: RegType::RtA,
-            reg_2: RegType::RtD,
-            cond: CondType::CtNone,
-            param: 0,
-        }),
-        0xAB => Some(Instruction {
-            type_in: InType::InXor,
-            mode: AddrMode::AmRr,
-            reg_1: RegType::RtA,
-            reg_2: RegType::RtE,
-            cond: CondType::CtNone,
-            param: 0,
-        }),
-        0xAC => Some(Instruction {
-            type_in: InType::InXor,
-            mode: AddrMode::AmRr,
-            reg_1: RegType::RtA,
-            reg_2: RegType::RtH,
-            cond: CondType::CtNone,
-            param: 0,
-        }),
-        0xAD => Some(Instruction {
-            type_in: InType::InXor,
-            mode: AddrMode::AmRr,
-            reg_1: RegType::RtA,
-            reg_2: RegType::RtL,
-            cond: CondType::CtNone,
-            param: 0,
-        }),
-        0xAE => Some(Instruction {
-            type_in: InType::InXor,
-            mode: AddrMode::AmRMr,
-            reg_1: RegType::RtA,
-            reg_2: RegType::RtHl,
-            cond: CondType::CtNone,
-            param: 0,
-        }),
-        0xAF => Some(Instruction {
-            type_in: InType::InXor,
-            mode: AddrMode::AmRr,
-            reg_1: RegType::RtA,
-            reg_2: RegType::RtA,
-            cond: CondType::CtNone,
-            param: 0,
-        }),
-        0xB0 => Some(Instruction {
-            type_in: InType::InOr,
-            mode: AddrMode::AmRr,
-            reg_1: RegType::RtA,
-            reg_2: RegType::RtB,
-            cond: CondType::CtNone,
-            param: 0,
-        }),
-        0xB1 => Some(Instruction {
-            type_in: InType::InOr,
-            mode: AddrMode::AmRr,
-            reg_1: RegType::RtA,
-            reg_2: RegType::RtC,
-            cond: CondType::CtNone,
-            param: 0,
-        }),
-        0xB2 => Some(Instruction {
-            type_in: InType::InOr,
-            mode: AddrMode::AmRr,
-            reg_1: RegType::RtA,
-            reg_2: RegType::RtD,
-            cond: CondType::CtNone,
-            param: 0,
-        }),
-        0xB3 => Some(Instruction {
-            type_in: InType::InOr,
-            mode: AddrMode::AmRr,
-            reg_1: RegType::RtA,
-            reg_2: RegType::RtE,
-            cond: CondType::CtNone,
-            param: 0,
-        }),
-        0xB4 => Some(Instruction {
-            type_in: InType::InOr,
-            mode: AddrMode::AmRr,
-            reg_1: RegType::RtA,
-            reg_2: RegType::RtH,
-            cond: CondType::CtNone,
-            param: 0,
-        }),
-        0xB5 => Some(Instruction {
-            type_in: InType::InOr,
-            mode: AddrMode::AmRr,
-            reg_1: RegType::RtA,
-            reg_2: RegType::RtL,
-            cond: CondType::CtNone,
-            param: 0,
-        }),
-        0xB6 => Some(Instruction {
-            type_in: InType::InOr,
-            mode: AddrMode::AmRMr,
-            reg_1: RegType::RtA,
-            reg_2: RegType::RtHl,
-            cond: CondType::CtNone,
-            param: 0,
-        }),
-        0xB7 => Some(Instruction {
-            type_in: InType::InOr,
-            mode: AddrMode::AmRr,
-            reg_1: RegType::RtA,
-            reg_2: RegType::RtA,
-            cond: CondType::CtNone,
-            param: 0,
-        }),
-        0xB8 => Some(Instruction {
-            type_in: InType::InCp,
-            mode: AddrMode::AmRr,
-            reg_1: RegType::RtA,
-            reg_2: RegType::RtB,
-            cond: CondType::CtNone,
-            param: 0,
-        }),
-        0xB9 => Some(Instruction {
-            type_in: InType::InCp,
-            mode: AddrMode::AmRr,
-            reg_1: RegType::RtA,
-            reg_2: RegType::RtC,
-            cond: CondType::CtNone,
-            param: 0,
-        }),
-        0xBA => Some(Instruction {
-            type_in: InType::InCp,
-            mode: AddrMode::AmRr,
-            reg_1: RegType::RtA,
-            reg_2: RegType::RtD,
-            cond: CondType::CtNone,
-            param: 0,
-        }),
-        0xBB => Some(Instruction {
-            type_in: InType::InCp,
-            mode: AddrMode::AmRr,
-            reg_1: RegType::RtA,
-            reg_2: RegType::RtE,
-            cond: CondType::CtNone,
-            param: 0,
-        }),
-        0xBC => Some(Instruction {
-            type_in: InType::InCp,
-            mode: AddrMode::AmRr,
-            reg_1: RegType::RtA,
-            reg_2: RegType::RtH,
-            cond: CondType::CtNone,
-            param: 0,
-        }),
-        0xBD => Some(Instruction {
-            type_in: InType::InCp,
-            mode: AddrMode::AmRr,
-            reg_1: RegType::RtA,
-            reg_2: RegType::RtL,
-            cond: CondType::CtNone,
-            param: 0,
-        }),
-        0xBE => Some(Instruction {
-            type_in: InType::InCp,
-            mode: AddrMode::AmRMr,
-            reg_1: RegType::RtA,
-            reg_2: RegType::RtHl,
-            cond: CondType::CtNone,
-            param: 0,
-        }),
-        0xBF => Some(Instruction {
-            type_in: InType::InCp,
-            mode: AddrMode::AmRr,
-            reg_1: RegType::RtA,
-            reg_2: RegType::RtA,
-            cond: CondType::CtNone,
-            param: 0,
-        }),
+pub const fn cb_decode_bit(op: u8) -> u8 {
+    (op >> 3) & 0b111
+}
 
-        0xC0 => Some(Instruction {
-            type_in: InType::InRet,
-            mode: AddrMode::AmImp,
-            reg_1: RegType::RtNone,
-            reg_2: RegType::RtNone,
-            cond: CondType::CtNz,
-            param: 0,
-        }),
-        0xC1 => Some(Instruction {
-            type_in: InType::InPop,
-            mode: AddrMode::AmR,
-            reg_1: RegType::RtBc,
-            reg_2: RegType::RtNone,
-            cond: CondType::CtNone,
-            param: 0,
-        }),
-        0xC2 => Some(Instruction {
-            type_in: InType::InJp,
-            mode: AddrMode::AmD16,
-            reg_1: RegType::RtNone,
-            reg_2: RegType::RtNone,
-            cond: CondType::CtNz,
-            param: 0,
-        }),
-        0xC3 => Some(Instruction {
-            type_in: InType::InJp,
-            mode: AddrMode::AmD16,
-            reg_1: RegType::RtNone,
-            reg_2: RegType::RtNone,
-            cond: CondType::CtNone,
-            param: 0,
-        }),
-        0xC4 => Some(Instruction {
-            type_in: InType::InCall,
-            mode: AddrMode::AmD16,
-            reg_1: RegType::RtNone,
-            reg_2: RegType::RtNone,
-            cond: CondType::CtNz,
-            param: 0,
-        }),
-        0xC5 => Some(Instruction {
-            type_in: InType::InPush,
-            mode: AddrMode::AmR,
-            reg_1: RegType::RtBc,
-            reg_2: RegType::RtNone,
-            cond: CondType::CtNone,
-            param: 0,
-        }),
-        0xC6 => Some(Instruction {
-            type_in: InType::InAdd,
-            mode: AddrMode::AmRa8,
-            reg_1: RegType::RtA,
-            reg_2: RegType::RtNone,
-            cond: CondType::CtNone,
-            param: 0,
-        }),
-        0xC7 => Some(Instruction {
-            type_in: InType::InRst,
-            mode: AddrMode::AmImp,
-            reg_1: RegType::RtNone,
-            reg_2: RegType::RtNone,
-            cond: CondType::CtNone,
-            param: 0x00,
-        }),
-        0xC8 => Some(Instruction {
-            type_in: InType::InRet,
-            mode: AddrMode::AmImp,
-            reg_1: RegType::RtNone,
-            reg_2: RegType::RtNone,
-            cond: CondType::CtZ,
-            param: 0,
-        }),
-        0xC9 => Some(Instruction {
-            type_in: InType::InRet,
-            mode: AddrMode::AmImp,
-            reg_1: RegType::RtNone,
-            reg_2: RegType::RtNone,
-            cond: CondType::CtNone,
-            param: 0,
-        }),
-        0xCA => Some(Instruction {
-            type_in: InType::InJp,
-            mode: AddrMode::AmD16,
-            reg_1: RegType::RtNone,
-            reg_2: RegType::RtNone,
-            cond: CondType::CtZ,
-            param: 0,
-        }),
-        0xCB => Some(Instruction {
-            type_in: InType::InCb,
-            mode: AddrMode::AmD8,
-            reg_1: RegType::RtNone,
-            reg_2: RegType::RtNone,
-            cond: CondType::CtNone,
-            param: 0,
-        }),
-        0xCC => Some(Instruction {
-            type_in: InType::InCall,
-            mode: AddrMode::AmD16,
-            reg_1: RegType::RtNone,
-            reg_2: RegType::RtNone,
-            cond: CondType::CtZ,
-            param: 0,
-        }),
-        0xCD => Some(Instruction {
-            type_in: InType::InCall,
-            mode: AddrMode::AmD16,
-            reg_1: RegType::RtNone,
-            reg_2: RegType::RtNone,
-            cond: CondType::CtNone,
-            param: 0,
-        }),
-        0xCE => Some(Instruction {
-            type_in: InType::InAdc,
-            mode: AddrMode::AmRD8,
-            reg_1: RegType::RtA,
-            reg_2: RegType::RtNone,
-            cond: CondType::CtNone,
-            param: 0,
-        }),
-        0xCF => Some(Instruction {
-            type_in: InType::InRst,
-            mode: AddrMode::AmImp,
-            reg_1: RegType::RtNone,
-            reg_2: RegType::RtNone,
-            cond: CondType::CtNone,
-            param: 0x08,
-        }),
-        0xD0 => Some(Instruction {
-            type_in: InType::InRet,
-            mode: AddrMode::AmImp,
-            reg_1: RegType::RtNone,
-            reg_2: RegType::RtNone,
-            cond: CondType::CtNc,
-            param: 0,
-        }),
-        0xD1 => Some(Instruction {
-            type_in: InType::InPop,
-            mode: AddrMode::AmR,
-            reg_1: RegType::RtDe,
-            reg_2: RegType::RtNone,
-            cond: CondType::CtNone,
-            param: 0,
-        }),
-        0xD2 => Some(Instruction {
-            type_in: InType::InJp,
-            mode: AddrMode::AmD16,
-            reg_1: RegType::RtNone,
-            reg_2: RegType::RtNone,
-            cond: CondType::CtNc,
-            param: 0,
-        }),
-        0xD4 => Some(Instruction {
-            type_in: InType::InCall,
-            mode: AddrMode::AmD16,
-            reg_1: RegType::RtNone,
-            reg_2: RegType::RtNone,
-            cond: CondType::CtNc,
-            param: 0,
-        }),
-        0xD5 => Some(Instruction {
-            type_in: InType::InPush,
-            mode: AddrMode::AmR,
-            reg_1: RegType::RtDe,
-            reg_2: RegType::RtNone,
-            cond: CondType::CtNone,
-            param: 0,
-        }),
-        0xD6 => Some(Instruction {
-            type_in: InType::InSub,
-            mode: AddrMode::AmD8,
-            reg_1: RegType::RtNone,
-            reg_2: RegType::RtNone,
-            cond: CondType::CtNone,
-            param: 0,
-        }),
-        0xD7 => Some(Instruction {
-            type_in: InType::InRst,
-            mode: AddrMode::AmImp,
-            reg_1: RegType::RtNone,
-            reg_2: RegType::RtNone,
-            cond: CondType::CtNone,
-            param: 0x10,
-        }),
-        0xD8 => Some(Instruction {
-            type_in: InType::InRet,
-            mode: AddrMode::AmImp,
-            reg_1: RegType::RtNone,
-            reg_2: RegType::RtNone,
-            cond: CondType::CtC,
-            param: 0,
-        }),
-        0xD9 => Some(Instruction {
-            type_in: InType::InReti,
-            mode: AddrMode::AmImp,
-            reg_1: RegType::RtNone,
-            reg_2: RegType::RtNone,
-            cond: CondType::CtNone,
-            param: 0,
-        }),
-        0xDA => Some(Instruction {
-            type_in: InType::InJp,
-            mode: AddrMode::AmD16,
-            reg_1: RegType::RtNone,
-            reg_2: RegType::RtNone,
-            cond: CondType::CtC,
-            param: 0,
-        }),
-        0xDC => Some(Instruction {
-            type_in: InType::InCall,
-            mode: AddrMode::AmD16,
-            reg_1: RegType::RtNone,
-            reg_2: RegType::RtNone,
-            cond: CondType::CtC,
-            param: 0,
-        }),
-        0xDE => Some(Instruction {
-            type_in: InType::InSbc,
-            mode: AddrMode::AmRD8,
-            reg_1: RegType::RtA,
-            reg_2: RegType::RtNone,
-            cond: CondType::CtNone,
-            param: 0,
-        }),
-        0xDF => Some(Instruction {
-            type_in: InType::InRst,
-            mode: AddrMode::AmImp,
-            reg_1: RegType::RtNone,
-            reg_2: RegType::RtNone,
-            cond: CondType::CtNone,
-            param: 0x18,
-        }),
+pub const fn cb_inst_type(op: u8) -> InType {
+    match op >> 6 {
+        0 => match cb_decode_bit(op) {
+            0 => InType::InRlc,
+            1 => InType::InRrc,
+            2 => InType::InRl,
+            3 => InType::InRr,
+            4 => InType::InSla,
+            5 => InType::InSra,
+            6 => InType::InSwap,
+            _ => InType::InSrl,
+        },
+        1 => InType::InBit,
+        2 => InType::InRes,
+        _ => InType::InSet,
+    }
+}
 
-        //0xEX
-        0xE0 => Some(Instruction {
-            type_in: InType::InLdh,
-            mode: AddrMode::AmA8r,
-            reg_1: RegType::RtNone,
-            reg_2: RegType::RtA,
-            cond: CondType::CtNone,
-            param: 0,
-        }),
-        0xE1 => Some(Instruction {
-            type_in: InType::InPop,
-            mode: AddrMode::AmR,
-            reg_1: RegType::RtHl,
-            reg_2: RegType::RtNone,
-            cond: CondType::CtNone,
-            param: 0,
-        }),
-        0xE2 => Some(Instruction {
-            type_in: InType::InLd,
-            mode: AddrMode::AmMrr,
-            reg_1: RegType::RtC,
-            reg_2: RegType::RtA,
-            cond: CondType::CtNone,
-            param: 0,
-        }),
-        0xE5 => Some(Instruction {
-            type_in: InType::InPush,
-            mode: AddrMode::AmR,
-            reg_1: RegType::RtHl,
-            reg_2: RegType::RtNone,
-            cond: CondType::CtNone,
-            param: 0,
-        }),
-        0xE6 => Some(Instruction {
-            type_in: InType::InAnd,
-            mode: AddrMode::AmD8,
-            reg_1: RegType::RtNone,
-            reg_2: RegType::RtNone,
-            cond: CondType::CtNone,
-            param: 0,
-        }),
-        0xE7 => Some(Instruction {
-            type_in: InType::InRst,
-            mode: AddrMode::AmImp,
-            reg_1: RegType::RtNone,
-            reg_2: RegType::RtNone,
-            cond: CondType::CtNone,
-            param: 0x20,
-        }),
-        0xE8 => Some(Instruction {
-            type_in: InType::InAdd,
-            mode: AddrMode::AmRD8,
-            reg_1: RegType::RtSp,
-            reg_2: RegType::RtNone,
-            cond: CondType::CtNone,
-            param: 0,
-        }),
-        0xE9 => Some(Instruction {
-            type_in: InType::InJp,
-            mode: AddrMode::AmMr,
-            reg_1: RegType::RtHl,
-            reg_2: RegType::RtNone,
-            cond: CondType::CtNone,
-            param: 0,
-        }),
-        0xEA => Some(Instruction {
-            type_in: InType::InLd,
-            mode: AddrMode::AmA16r,
-            reg_1: RegType::RtNone,
-            reg_2: RegType::RtA,
-            cond: CondType::CtNone,
-            param: 0,
-        }),
-        0xEE => Some(Instruction {
-            type_in: InType::InXor,
-            mode: AddrMode::AmD8,
-            reg_1: RegType::RtNone,
-            reg_2: RegType::RtNone,
-            cond: CondType::CtNone,
-            param: 0,
-        }),
-        0xEF => Some(Instruction {
-            type_in: InType::InRst,
-            mode: AddrMode::AmImp,
-            reg_1: RegType::RtNone,
-            reg_2: RegType::RtNone,
-            cond: CondType::CtNone,
-            param: 0x28,
-        }),
+// (HL) operands use AmMr, everything else operates on a register. The bit
+// index is kept in `param`.
+const fn cb_instructions() -> [Instruction; 256] {
+    let mut table = [NONE; 256];
+    let mut op = 0;
 
-        //0xFX
-        0xF0 => Some(Instruction {
-            type_in: InType::InLdh,
-            mode: AddrMode::AmRa8,
-            reg_1: RegType::RtA,
-            reg_2: RegType::RtNone,
-            cond: CondType::CtNone,
-            param: 0,
-        }),
-        0xF1 => Some(Instruction {
-            type_in: InType::InPop,
-            mode: AddrMode::AmR,
-            reg_1: RegType::RtAf,
-            reg_2: RegType::RtNone,
-            cond: CondType::CtNone,
-            param: 0,
-        }),
-        0xF2 => Some(Instruction {
-            type_in: InType::InLd,
-            mode: AddrMode::AmRMr,
-            reg_1: RegType::RtA,
-            reg_2: RegType::RtC,
-            cond: CondType::CtNone,
-            param: 0,
-        }),
-        0xF3 => Some(Instruction {
-            type_in: InType::InDi,
-            mode: AddrMode::AmImp,
-            reg_1: RegType::RtNone,
-            reg_2: RegType::RtNone,
-            cond: CondType::CtNone,
-            param: 0,
-        }),
-        0xF5 => Some(Instruction {
-            type_in: InType::InPush,
-            mode: AddrMode::AmR,
-            reg_1: RegType::RtAf,
-            reg_2: RegType::RtNone,
-            cond: CondType::CtNone,
-            param: 0,
-        }),
-        0xF6 => Some(Instruction {
-            type_in: InType::InOr,
-            mode: AddrMode::AmD8,
-            reg_1: RegType::RtNone,
-            reg_2: RegType::RtNone,
-            cond: CondType::CtNone,
-            param: 0,
-        }),
-        0xF7 => Some(Instruction {
-            type_in: InType::InRst,
-            mode: AddrMode::AmImp,
-            reg_1: RegType::RtNone,
-            reg_2: RegType::RtNone,
-            cond: CondType::CtNone,
-            param: 0x30,
-        }),
-        0xF8 => Some(Instruction {
-            type_in: InType::InLd,
-            mode: AddrMode::AmHlspr,
-            reg_1: RegType::RtHl,
-            reg_2: RegType::RtSp,
-            cond: CondType::CtNone,
-            param: 0,
-        }),
-        0xF9 => Some(Instruction {
-            type_in: InType::InLd,
-            mode: AddrMode::AmR,
-            reg_1: RegType::RtSp,
-            reg_2: RegType::RtHl,
-            cond: CondType::CtNone,
-            param: 0,
-        }),
-        0xFA => Some(Instruction {
-            type_in: InType::InLd,
-            mode: AddrMode::AmRa16,
-            reg_1: RegType::RtA,
-            reg_2: RegType::RtNone,
-            cond: CondType::CtNone,
-            param: 0,
-        }),
-        0xFB => Some(Instruction {
-            type_in: InType::InEi,
-            mode: AddrMode::AmImp,
-            reg_1: RegType::RtNone,
-            reg_2: RegType::RtNone,
-            cond: CondType::CtNone,
-            param: 0,
-        }),
-        0xFE => Some(Instruction {
-            type_in: InType::InCp,
-            mode: AddrMode::AmD8,
-            reg_1: RegType::RtNone,
-            reg_2: RegType::RtNone,
-            cond: CondType::CtNone,
-            param: 0,
-        }),
-        0xFF => Some(Instruction {
-            type_in: InType::InRst,
-            mode: AddrMode::AmImp,
-            reg_1: RegType::RtNone,
-            reg_2: RegType::RtNone,
-            cond: CondType::CtNone,
-            param: 0x38,
-        }),
+    while op < 256 {
+        let reg = cb_decode_reg(op as u8);
+        let mode = match reg {
+            RegType::RtHl => AddrMode::AmMr,
+            _ => AddrMode::AmR,
+        };
 
-        _ => panic!("Unknown Opcode: {:02X}", opcode), // Only panic here
+        table[op] = inst(
+            cb_inst_type(op as u8),
+            mode,
+            reg,
+            RegType::RtNone,
+            CondType::CtNone,
+            cb_decode_bit(op as u8),
+        );
+        op += 1;
     }
+
+    table
 }
 
 const INST_LOOKUP: [&str; 48] = [
     "<NONE>", "NOP", "LD", "INC", "DEC", "RLCA", "ADD", "RRCA", "STOP", "RLA", "JR", "RRA", "DAA",
     "CPL", "SCF", "CCF", "HALT", "ADC", "SUB", "SBC", "AND", "XOR", "OR", "CP", "POP", "JP",
-    "PUSH", "RET", "CB", "CALL", "RETI", "LDH", "JPHL", "DI", "EI", "RST", "IN_ERR", "IN_RLC",
-    "IN_RRC", "IN_RL", "IN_RR", "IN_SLA", "IN_SRA", "IN_SWAP", "IN_SRL", "IN_BIT", "IN_RES",
-    "IN_SET",
+    "PUSH", "RET", "CB", "CALL", "RETI", "LDH", "JPHL", "DI", "EI", "RST", "IN_ERR", "RLC",
+    "RRC", "RL", "RR", "SLA", "SRA", "SWAP", "SRL", "BIT", "RES", "SET",
 ];
 
 // Function to lookup the instruction name based on `InType`
@@ -0,0 +1,57 @@
+use crate::bus::Bus;
+use crate::cpu::CpuContext;
+
+// Bit positions in IE/IF, lowest bit has the highest priority.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum InterruptType {
+    Vblank = 1,
+    LcdStat = 2,
+    Timer = 4,
+    Serial = 8,
+    Joypad = 16,
+}
+
+const INTERRUPTS: [(InterruptType, u16); 5] = [
+    (InterruptType::Vblank, 0x40),
+    (InterruptType::LcdStat, 0x48),
+    (InterruptType::Timer, 0x50),
+    (InterruptType::Serial, 0x58),
+    (InterruptType::Joypad, 0x60),
+];
+
+impl<B: Bus> CpuContext<B> {
+    pub fn cpu_request_interrupt(&mut self, it: InterruptType) {
+        self.cpu_set_int_flags(self.cpu_get_int_flags() | it as u8);
+    }
+
+    pub fn cpu_get_int_flags(&self) -> u8 {
+        self.bus.int_flags()
+    }
+
+    pub fn cpu_set_int_flags(&mut self, value: u8) {
+        self.bus.set_int_flags(value);
+    }
+
+    // Interrupts that are both requested and enabled, regardless of IME.
+    pub fn cpu_pending_interrupts(&self) -> u8 {
+        self.bus.int_enable() & self.bus.int_flags() & 0x1F
+    }
+
+    // Services the highest priority pending interrupt: two wait states,
+    // PC pushed high byte first, then the jump to the vector (5 M-cycles).
+    pub fn cpu_handle_interrupts(&mut self) {
+        let pending = self.cpu_pending_interrupts();
+
+        if let Some(&(it, vector)) = INTERRUPTS.iter().find(|(it, _)| pending & *it as u8 != 0) {
+            self.cpu_set_int_flags(self.cpu_get_int_flags() & !(it as u8));
+            self.int_master_enable = false;
+            self.halted = false;
+
+            self.emu_cycle(2);
+            self.stack_push16(self.regs.pc);
+            self.emu_cycle(2);
+            self.regs.pc = vector;
+            self.emu_cycle(1);
+        }
+    }
+}
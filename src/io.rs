@@ -0,0 +1,150 @@
+use crate::apu::ApuContext;
+use crate::bus::BusContext;
+use crate::joypad::{Button, JoypadContext};
+use crate::model::Model;
+use crate::ppu::LcdMode;
+
+// M-cycles the CPU is held for while the clock changes speed.
+const SPEED_SWITCH_CYCLES: u16 = 2050;
+
+// 0xFF00 - 0xFF7F state that has no subsystem of its own here. The timer,
+// serial port, PPU, OAM and VRAM DMA, WRAM bank and IF live on the bus and
+// are routed to from io_read and io_write.
+pub struct IoRegisters {
+    pub joypad: JoypadContext,
+    pub apu: ApuContext,
+    pub boot_rom_disabled: bool,
+    // CGB only. KEY1 bit 7 is the current speed, bit 0 arms a switch.
+    pub key1: u8,
+    // M-cycles left of a speed switch, the CPU and DIV are held meanwhile.
+    pub speed_switch: u16,
+    pub rp: u8,
+    pub bcps: u8,
+    pub ocps: u8,
+    pub bg_palettes: [u8; 0x40],
+    pub obj_palettes: [u8; 0x40],
+    pub opri: u8,
+    // 0xFF72 - 0xFF75, undocumented but readable.
+    pub undocumented: [u8; 4],
+}
+
+impl Default for IoRegisters {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl IoRegisters {
+    pub fn new() -> Self {
+        IoRegisters {
+            joypad: JoypadContext::new(),
+            apu: ApuContext::new(),
+            boot_rom_disabled: true,
+            key1: 0,
+            speed_switch: 0,
+            rp: 0,
+            bcps: 0,
+            ocps: 0,
+            bg_palettes: [0xFF; 0x40],
+            obj_palettes: [0xFF; 0x40],
+            opri: 0,
+            undocumented: [0; 4],
+        }
+    }
+}
+
+// BCPD/OCPD go through the index in BCPS/OCPS, which auto-increments on
+// writes when bit 7 is set.
+fn palette_write(spec: &mut u8, palettes: &mut [u8; 0x40], value: u8) {
+    palettes[(*spec & 0x3F) as usize] = value;
+    if *spec & 0x80 != 0 {
+        *spec = 0x80 | (spec.wrapping_add(1) & 0x3F);
+    }
+}
+
+impl<'a> BusContext<'a> {
+    // Unused bits read as 1, as do registers that aren't there at all.
+    pub fn io_read(&self, address: u16) -> u8 {
+        let cgb = self.model == Model::Cgb;
+        let io = &self.io;
+
+        match address {
+            0xFF00 => io.joypad.joypad_read(),
+            0xFF01 | 0xFF02 => self.serial.serial_read(address),
+            0xFF04..=0xFF07 => self.timer.timer_read(address),
+            0xFF0F => 0xE0 | self.int_flags,
+            0xFF10..=0xFF3F => io.apu.apu_read(address),
+            0xFF46 => self.dma.page,
+            0xFF40..=0xFF4B => self.ppu.ppu_read(address),
+            0xFF4D if cgb => 0x7E | io.key1,
+            0xFF4F if cgb => 0xFE | self.ppu.vmem.vbk,
+            0xFF51..=0xFF55 if cgb => self.hdma.hdma_read(address),
+            0xFF56 if cgb => 0x3C | io.rp,
+            0xFF68 if cgb => 0x40 | io.bcps,
+            0xFF69 if cgb => io.bg_palettes[(io.bcps & 0x3F) as usize],
+            0xFF6A if cgb => 0x40 | io.ocps,
+            0xFF6B if cgb => io.obj_palettes[(io.ocps & 0x3F) as usize],
+            0xFF6C if cgb => 0xFE | io.opri,
+            0xFF70 if cgb => 0xF8 | self.ram.svbk,
+            0xFF72..=0xFF74 if cgb => io.undocumented[(address - 0xFF72) as usize],
+            0xFF75 if cgb => 0x8F | io.undocumented[3],
+            // PCM12/PCM34, the channel outputs are always silent.
+            0xFF76 | 0xFF77 if cgb => 0x00,
+            _ => 0xFF,
+        }
+    }
+
+    // STOP with KEY1 bit 0 set changes the CPU speed instead of stopping.
+    // Returns whether it did.
+    pub fn key1_switch(&mut self) -> bool {
+        if self.model != Model::Cgb || self.io.key1 & 0x01 == 0 {
+            return false;
+        }
+
+        self.io.key1 = (self.io.key1 ^ 0x80) & 0x80;
+        self.io.speed_switch = SPEED_SWITCH_CYCLES;
+        true
+    }
+
+    pub fn double_speed(&self) -> bool {
+        self.io.key1 & 0x80 != 0
+    }
+
+    pub fn joypad_set(&mut self, button: Button, pressed: bool) {
+        self.int_flags |= self.io.joypad.joypad_set(button, pressed);
+    }
+
+    // Writes to read-only bits and missing registers are dropped.
+    pub fn io_write(&mut self, address: u16, value: u8) {
+        let cgb = self.model == Model::Cgb;
+        let io = &mut self.io;
+
+        match address {
+            0xFF00 => io.joypad.joypad_write(value),
+            0xFF01 | 0xFF02 => self.serial.serial_write(address, value),
+            0xFF04..=0xFF07 => self.timer.timer_write(address, value),
+            0xFF0F => self.int_flags = value & 0x1F,
+            0xFF10..=0xFF3F => io.apu.apu_write(address, value),
+            0xFF46 => self.dma.dma_start(value),
+            0xFF40..=0xFF4B => self.ppu.ppu_write(address, value),
+            0xFF4D if cgb => io.key1 = (io.key1 & 0x80) | (value & 0x01),
+            0xFF4F if cgb => self.ppu.vmem.vbk = value & 0x01,
+            // Only a write can disable the boot ROM, never re-enable it.
+            0xFF50 => io.boot_rom_disabled |= value & 0x01 != 0,
+            0xFF51..=0xFF55 if cgb => {
+                let in_hblank = self.ppu.mode == LcdMode::HBlank;
+                self.hdma.hdma_write(address, value, in_hblank)
+            }
+            0xFF56 if cgb => io.rp = value & 0xC1,
+            0xFF68 if cgb => io.bcps = value & 0xBF,
+            0xFF69 if cgb => palette_write(&mut io.bcps, &mut io.bg_palettes, value),
+            0xFF6A if cgb => io.ocps = value & 0xBF,
+            0xFF6B if cgb => palette_write(&mut io.ocps, &mut io.obj_palettes, value),
+            0xFF6C if cgb => io.opri = value & 0x01,
+            0xFF70 if cgb => self.ram.svbk = value & 0x07,
+            0xFF72..=0xFF74 if cgb => io.undocumented[(address - 0xFF72) as usize] = value,
+            0xFF75 if cgb => io.undocumented[3] = value & 0x70,
+            _ => (),
+        }
+    }
+}
@@ -0,0 +1,82 @@
+use crate::interrupts::InterruptType;
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Button {
+    Right = 0x01,
+    Left = 0x02,
+    Up = 0x04,
+    Down = 0x08,
+    A = 0x10,
+    B = 0x20,
+    Select = 0x40,
+    Start = 0x80,
+}
+
+pub struct JoypadContext {
+    // P1 bits 4-5, a 0 selects the direction or action buttons.
+    pub select: u8,
+    // Held buttons, directions in the low nibble and actions in the high.
+    pub pressed: u8,
+    // A selected line went low since the last joypad_take_edge, what
+    // wakes the CPU from STOP.
+    edge: bool,
+}
+
+impl Default for JoypadContext {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl JoypadContext {
+    pub fn new() -> Self {
+        JoypadContext {
+            select: 0x30,
+            pressed: 0,
+            edge: false,
+        }
+    }
+
+    // Lines of the selected groups, active low.
+    fn joypad_lines(&self) -> u8 {
+        let mut lines = 0;
+        if self.select & 0x10 == 0 {
+            lines |= self.pressed & 0x0F;
+        }
+        if self.select & 0x20 == 0 {
+            lines |= self.pressed >> 4;
+        }
+        !lines & 0x0F
+    }
+
+    // Updates a button, returning the joypad interrupt when a selected line
+    // goes low.
+    pub fn joypad_set(&mut self, button: Button, pressed: bool) -> u8 {
+        let before = self.joypad_lines();
+
+        if pressed {
+            self.pressed |= button as u8;
+        } else {
+            self.pressed &= !(button as u8);
+        }
+
+        if before & !self.joypad_lines() != 0 {
+            self.edge = true;
+            InterruptType::Joypad as u8
+        } else {
+            0
+        }
+    }
+
+    pub fn joypad_take_edge(&mut self) -> bool {
+        std::mem::take(&mut self.edge)
+    }
+
+    pub fn joypad_read(&self) -> u8 {
+        0xC0 | self.select | self.joypad_lines()
+    }
+
+    pub fn joypad_write(&mut self, value: u8) {
+        self.select = value & 0x30;
+    }
+}
@@ -1,3 +1,4 @@
+pub mod apu;
 pub mod bus;
 pub mod cart;
 pub mod common;
@@ -5,9 +6,22 @@ pub mod cpu;
 pub mod cpu_proc;
 pub mod cpu_uitil;
 pub mod cpu_fetch;
+pub mod disasm;
+pub mod dma;
+#[cfg(feature = "sdl")]
 pub mod emu;
+pub mod error;
+pub mod hdma;
+pub mod headless;
 pub mod instructions;
+pub mod interrupts;
+pub mod io;
+pub mod joypad;
+pub mod model;
 pub mod ppu;
 pub mod ram;
+pub mod serial;
 pub mod stack;
 pub mod timer;
+pub mod trace;
+pub mod vmem;
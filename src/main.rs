@@ -1,7 +1,39 @@
 use gameboy::emu;
-use std::env;
+use gameboy::error::EmuError;
+use gameboy::headless::{self, TestStatus};
+use gameboy::model::Model;
+use std::{env, process};
+
+fn run_headless(rom: &str) -> Result<bool, EmuError> {
+    let report = headless::run_test_rom(rom, Model::Dmg, headless::MAX_CYCLES)?;
+
+    print!("{}", report.serial);
+    if report.serial.is_empty() {
+        print!("{}", report.memory);
+    }
+    println!();
+    println!("{:?} after {} M-cycles", report.status, report.cycles);
+
+    Ok(report.status == TestStatus::Passed)
+}
+
 fn main() {
     let args: Vec<String> = env::args().collect();
-    let mut emu_context: emu::emu_context = emu::emu_context::new();
-    emu_context.emu_run(args);
+
+    if args.len() > 2 && args[1] == "--headless" {
+        match run_headless(&args[2]) {
+            Ok(true) => process::exit(0),
+            Ok(false) => process::exit(1),
+            Err(err) => {
+                eprintln!("{}", err);
+                process::exit(1);
+            }
+        }
+    }
+
+    let mut emu_context: emu::EmuContext = emu::EmuContext::new();
+    if let Err(err) = emu_context.emu_run(args) {
+        eprintln!("{}", err);
+        process::exit(1);
+    }
 }
@@ -0,0 +1,8 @@
+// Hardware revision being emulated. Decides which CGB registers and banks
+// exist, and what the boot ROM leaves behind.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Model {
+    #[default]
+    Dmg,
+    Cgb,
+}
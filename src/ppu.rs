@@ -1,2 +1,151 @@
-fn ppu_init(){}
-fn ppu_tick(){}
+use crate::interrupts::InterruptType;
+use crate::vmem::VmemContext;
+
+pub const LINES_PER_FRAME: u8 = 154;
+pub const TICKS_PER_LINE: u32 = 456;
+pub const YRES: u8 = 144;
+
+const OAM_TICKS: u32 = 80;
+const XFER_TICKS: u32 = 172;
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum LcdMode {
+    HBlank = 0,
+    VBlank = 1,
+    Oam = 2,
+    Xfer = 3,
+}
+
+pub struct PpuContext {
+    pub mode: LcdMode,
+    pub ly: u8,
+    pub line_ticks: u32,
+    pub current_frame: u64,
+    pub vmem: VmemContext,
+    // LCD registers, 0xFF40 - 0xFF4B.
+    pub lcdc: u8,
+    pub stat: u8,
+    pub scy: u8,
+    pub scx: u8,
+    pub lyc: u8,
+    pub bgp: u8,
+    pub obp0: u8,
+    pub obp1: u8,
+    pub wy: u8,
+    pub wx: u8,
+}
+
+impl Default for PpuContext {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl PpuContext {
+    pub fn new() -> Self {
+        PpuContext {
+            mode: LcdMode::Oam,
+            ly: 0,
+            line_ticks: 0,
+            current_frame: 0,
+            vmem: VmemContext::new(),
+            lcdc: 0x91,
+            stat: 0,
+            scy: 0,
+            scx: 0,
+            lyc: 0,
+            bgp: 0xFC,
+            obp0: 0,
+            obp1: 0,
+            wy: 0,
+            wx: 0,
+        }
+    }
+
+    fn lcd_enabled(&self) -> bool {
+        self.lcdc & 0x80 != 0
+    }
+
+    // Advances the PPU by one dot (T-cycle), returning any interrupt
+    // requested.
+    pub fn ppu_tick(&mut self) -> u8 {
+        let mut int = 0;
+
+        // With the LCD off the PPU sits at the start of line 0.
+        if !self.lcd_enabled() {
+            return int;
+        }
+
+        self.line_ticks += 1;
+
+        if self.line_ticks == TICKS_PER_LINE {
+            self.line_ticks = 0;
+            self.ly += 1;
+
+            if self.ly == LINES_PER_FRAME {
+                self.ly = 0;
+                self.current_frame += 1;
+            }
+        }
+
+        let mode = if self.ly >= YRES {
+            LcdMode::VBlank
+        } else if self.line_ticks < OAM_TICKS {
+            LcdMode::Oam
+        } else if self.line_ticks < OAM_TICKS + XFER_TICKS {
+            LcdMode::Xfer
+        } else {
+            LcdMode::HBlank
+        };
+
+        if mode != self.mode && mode == LcdMode::VBlank {
+            int |= InterruptType::Vblank as u8;
+        }
+
+        self.mode = mode;
+        int
+    }
+
+    pub fn ppu_read(&self, address: u16) -> u8 {
+        match address {
+            0xFF40 => self.lcdc,
+            0xFF41 => {
+                let coincidence = if self.ly == self.lyc { 0x04 } else { 0 };
+                0x80 | (self.stat & 0x78) | coincidence | self.mode as u8
+            }
+            0xFF42 => self.scy,
+            0xFF43 => self.scx,
+            0xFF44 => self.ly,
+            0xFF45 => self.lyc,
+            0xFF47 => self.bgp,
+            0xFF48 => self.obp0,
+            0xFF49 => self.obp1,
+            0xFF4A => self.wy,
+            _ => self.wx,
+        }
+    }
+
+    pub fn ppu_write(&mut self, address: u16, value: u8) {
+        match address {
+            0xFF40 => {
+                self.lcdc = value;
+
+                if !self.lcd_enabled() {
+                    self.ly = 0;
+                    self.line_ticks = 0;
+                    self.mode = LcdMode::HBlank;
+                }
+            }
+            0xFF41 => self.stat = value & 0x78,
+            0xFF42 => self.scy = value,
+            0xFF43 => self.scx = value,
+            0xFF44 => (), // LY is read only
+            0xFF45 => self.lyc = value,
+            0xFF47 => self.bgp = value,
+            0xFF48 => self.obp0 = value,
+            0xFF49 => self.obp1 = value,
+            0xFF4A => self.wy = value,
+            _ => self.wx = value,
+        }
+    }
+}
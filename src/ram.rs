@@ -1,15 +1,35 @@
+// 0xC000 - 0xCFFF is always bank 0 of WRAM, 0xD000 - 0xDFFF shows the
+// bank picked by SVBK (0xFF70). Only CGB has more than one, a bank of 0
+// selects 1.
 pub struct RamContext {
-    wram: [u8; 0x2000],
+    wram: [u8; 0x8000],
     hram: [u8; 0x80],
+    pub svbk: u8,
+}
+
+impl Default for RamContext {
+    fn default() -> Self {
+        Self::new()
+    }
 }
 
 impl RamContext {
     pub fn new() -> Self {
         RamContext {
-            wram: [0; 0x2000],
+            wram: [0; 0x8000],
             hram: [0; 0x80],
+            svbk: 0,
         }
     }
+
+    // `offset` is from 0xC000.
+    fn wram_index(&self, offset: u16) -> usize {
+        let bank = match offset {
+            0x0000..=0x0FFF => 0,
+            _ => (self.svbk & 0x07).max(1) as usize,
+        };
+        bank * 0x1000 + (offset & 0x0FFF) as usize
+    }
 }
 
 pub trait RamReadWrite {
@@ -20,26 +40,24 @@ pub trait RamReadWrite {
 }
 
 impl RamReadWrite for RamContext {
+    // Addresses outside 0xC000 - 0xDFFF wrap into it, as echo RAM does.
     fn wram_read(&self, addr: u16) -> u8 {
-        let address = addr - 0xC000;
-        if address >= 0x2000 {
-            panic!("INVALID WRAM ADDR {:08X}", address + 0xC000)
-        }
-        self.wram[address as usize]
+        let address = addr.wrapping_sub(0xC000) & 0x1FFF;
+        self.wram[self.wram_index(address)]
     }
 
     fn wram_write(&mut self, addr: u16, value: u8) {
-        let address = addr - 0xC000;
-        self.wram[address as usize] = value;
+        let index = self.wram_index(addr.wrapping_sub(0xC000) & 0x1FFF);
+        self.wram[index] = value;
     }
 
     fn hram_read(&self, addr: u16) -> u8 {
-        let address = addr -  0xFF80;
+        let address = addr.wrapping_sub(0xFF80) & 0x7F;
         self.hram[address as usize]
     }
 
     fn hram_write(&mut self, addr: u16, value: u8) {
-        let address = addr - 0xFF80;
+        let address = addr.wrapping_sub(0xFF80) & 0x7F;
         self.hram[address as usize] = value;
     }
 }
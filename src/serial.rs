@@ -0,0 +1,147 @@
+use std::cell::RefCell;
+use std::rc::Rc;
+
+use crate::interrupts::InterruptType;
+
+// The internal clock shifts a bit every 512 T-cycles (8192 Hz).
+const TICKS_PER_BIT: u16 = 512;
+
+// Whatever is plugged into the link port.
+pub trait SerialDevice {
+    // Exchanges a byte with the other end when a transfer starts: `out` is
+    // the byte being shifted out, the return value is shifted in.
+    fn serial_transfer(&mut self, out: u8) -> u8;
+
+    // In external clock mode the other end drives the clock, return true to
+    // shift one bit this T-cycle.
+    fn serial_external_clock(&mut self) -> bool {
+        false
+    }
+}
+
+// Nothing attached, the data line floats high and no external clock ever
+// arrives.
+pub struct Disconnected;
+
+impl SerialDevice for Disconnected {
+    fn serial_transfer(&mut self, _out: u8) -> u8 {
+        0xFF
+    }
+}
+
+// Records every byte sent, test ROMs print their results this way. Clones
+// share the same buffer, keep one to read the output back.
+#[derive(Clone, Default)]
+pub struct SerialCapture {
+    pub output: Rc<RefCell<Vec<u8>>>,
+}
+
+impl SerialCapture {
+    pub fn text(&self) -> String {
+        String::from_utf8_lossy(&self.output.borrow()).into_owned()
+    }
+}
+
+impl SerialDevice for SerialCapture {
+    fn serial_transfer(&mut self, out: u8) -> u8 {
+        self.output.borrow_mut().push(out);
+        0xFF
+    }
+}
+
+pub struct SerialContext {
+    pub sb: u8,
+    pub sc: u8,
+    device: Box<dyn SerialDevice>,
+    // Byte coming in from the device and bits left in the transfer.
+    incoming: u8,
+    bits_left: u8,
+    bit_ticks: u16,
+}
+
+impl Default for SerialContext {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl SerialContext {
+    pub fn new() -> Self {
+        SerialContext {
+            sb: 0,
+            sc: 0,
+            device: Box::new(Disconnected),
+            incoming: 0xFF,
+            bits_left: 0,
+            bit_ticks: 0,
+        }
+    }
+
+    pub fn serial_connect(&mut self, device: Box<dyn SerialDevice>) {
+        self.device = device;
+    }
+
+    fn serial_active(&self) -> bool {
+        self.sc & 0x80 != 0
+    }
+
+    fn serial_internal_clock(&self) -> bool {
+        self.sc & 0x01 != 0
+    }
+
+    // Shifts SB left one bit, MSB out and the next incoming bit in.
+    fn serial_shift(&mut self) -> u8 {
+        self.bits_left -= 1;
+        let bit = (self.incoming >> self.bits_left) & 1;
+        self.sb = (self.sb << 1) | bit;
+
+        if self.bits_left == 0 {
+            self.sc &= 0x7F;
+            return InterruptType::Serial as u8;
+        }
+        0
+    }
+
+    // Advances the serial port by one T-cycle, returning any interrupt
+    // requested.
+    pub fn serial_tick(&mut self) -> u8 {
+        if !self.serial_active() || self.bits_left == 0 {
+            return 0;
+        }
+
+        if self.serial_internal_clock() {
+            self.bit_ticks += 1;
+            if self.bit_ticks < TICKS_PER_BIT {
+                return 0;
+            }
+            self.bit_ticks = 0;
+        } else if !self.device.serial_external_clock() {
+            return 0;
+        }
+
+        self.serial_shift()
+    }
+
+    pub fn serial_read(&self, address: u16) -> u8 {
+        match address {
+            0xFF01 => self.sb,
+            _ => 0x7E | self.sc,
+        }
+    }
+
+    pub fn serial_write(&mut self, address: u16, value: u8) {
+        match address {
+            0xFF01 => self.sb = value,
+            _ => {
+                self.sc = value & 0x81;
+
+                // Setting bit 7 starts a transfer of the current SB.
+                if self.serial_active() {
+                    self.incoming = self.device.serial_transfer(self.sb);
+                    self.bits_left = 8;
+                    self.bit_ticks = 0;
+                }
+            }
+        }
+    }
+}
@@ -1,14 +1,15 @@
+use crate::bus::Bus;
 use crate::cpu::CpuContext;
 
-impl<'a> CpuContext<'a> {
+impl<B: Bus> CpuContext<B> {
     pub fn stack_push(&mut self, data: u8) {
-        self.regs.sp = self.regs.pc.wrapping_sub(1);
+        self.regs.sp = self.regs.sp.wrapping_sub(1);
         self.bus_write(self.regs.sp, data);
     }
 
     pub fn stack_push16(&mut self, data: u16) {
-        self.stack_push((data >> 8) as u8 & 0xFF);
-        self.stack_push(data as u8 & 0xFF);
+        self.stack_push((data >> 8) as u8);
+        self.stack_push(data as u8);
     }
 
     pub fn stack_pop(&mut self) -> u8 {
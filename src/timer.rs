@@ -1,2 +1,94 @@
-fn timer_init(){}
-fn timer_tick(){}
\ No newline at end of file
+use crate::interrupts::InterruptType;
+
+// DIV bit whose falling edge clocks TIMA, indexed by TAC bits 0-1.
+const TAC_DIV_BITS: [u16; 4] = [9, 3, 5, 7];
+
+pub struct TimerContext {
+    pub div: u16,
+    pub tima: u8,
+    pub tma: u8,
+    pub tac: u8,
+    // T-cycles left until TIMA is reloaded from TMA after an overflow.
+    reload_ticks: u8,
+}
+
+impl Default for TimerContext {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl TimerContext {
+    pub fn new() -> Self {
+        TimerContext {
+            div: 0,
+            tima: 0,
+            tma: 0,
+            tac: 0,
+            reload_ticks: 0,
+        }
+    }
+
+    // TIMA is clocked by the falling edge of (selected DIV bit AND enable),
+    // which is also why writing DIV or TAC can bump TIMA.
+    fn timer_signal(&self) -> bool {
+        let bit = TAC_DIV_BITS[(self.tac & 0b11) as usize];
+        self.tac & 0b100 != 0 && self.div & (1 << bit) != 0
+    }
+
+    fn timer_increment(&mut self) {
+        let (tima, overflow) = self.tima.overflowing_add(1);
+        self.tima = tima;
+
+        // TIMA reads 0 for one M-cycle before the reload and interrupt.
+        if overflow {
+            self.reload_ticks = 4;
+        }
+    }
+
+    fn timer_update<F: FnOnce(&mut Self)>(&mut self, f: F) {
+        let prev = self.timer_signal();
+        f(self);
+        if prev && !self.timer_signal() {
+            self.timer_increment();
+        }
+    }
+
+    // Advances the timer by one T-cycle, returning any interrupt requested.
+    pub fn timer_tick(&mut self) -> u8 {
+        let mut int = 0;
+
+        if self.reload_ticks > 0 {
+            self.reload_ticks -= 1;
+            if self.reload_ticks == 0 {
+                self.tima = self.tma;
+                int = InterruptType::Timer as u8;
+            }
+        }
+
+        self.timer_update(|t| t.div = t.div.wrapping_add(1));
+        int
+    }
+
+    pub fn timer_read(&self, address: u16) -> u8 {
+        match address {
+            0xFF04 => (self.div >> 8) as u8,
+            0xFF05 => self.tima,
+            0xFF06 => self.tma,
+            _ => 0xF8 | self.tac,
+        }
+    }
+
+    pub fn timer_write(&mut self, address: u16, value: u8) {
+        match address {
+            0xFF04 => self.timer_update(|t| t.div = 0),
+            0xFF05 => {
+                // Writing TIMA during the overflow cycle cancels the reload.
+                self.tima = value;
+                self.reload_ticks = 0;
+            }
+            0xFF06 => self.tma = value,
+            _ => self.timer_update(|t| t.tac = value & 0b111),
+        }
+    }
+}
@@ -0,0 +1,240 @@
+use std::collections::VecDeque;
+use std::fmt;
+use std::fs::File;
+use std::io::{self, BufRead, BufReader, BufWriter, Write};
+use std::ops::RangeInclusive;
+
+use crate::bus::Bus;
+use crate::cpu::CpuRegister;
+use crate::disasm::disassemble;
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum TraceFormat {
+    // Gameboy Doctor's reference log line:
+    // A:01 F:B0 B:00 C:13 D:00 E:D8 H:01 L:4D SP:FFFE PC:0100 PCMEM:00,C3,13,02
+    Doctor,
+    // M-cycle counter, disassembly and registers with named flags.
+    Rich,
+}
+
+pub fn doctor_line<B: Bus>(regs: &CpuRegister, bus: &B) -> String {
+    let pc = regs.pc;
+    format!(
+        "A:{:02X} F:{:02X} B:{:02X} C:{:02X} D:{:02X} E:{:02X} H:{:02X} L:{:02X} SP:{:04X} PC:{:04X} PCMEM:{:02X},{:02X},{:02X},{:02X}",
+        regs.a,
+        regs.f(),
+        regs.b,
+        regs.c,
+        regs.d,
+        regs.e,
+        regs.h,
+        regs.l,
+        regs.sp,
+        pc,
+        bus.peek(pc),
+        bus.peek(pc.wrapping_add(1)),
+        bus.peek(pc.wrapping_add(2)),
+        bus.peek(pc.wrapping_add(3)),
+    )
+}
+
+// Parses an inclusive "start-end" range for the trace filters, PCs are
+// hex and frames decimal.
+fn parse_range<T: PartialOrd>(
+    s: &str,
+    parse: impl Fn(&str) -> Option<T>,
+) -> Option<RangeInclusive<T>> {
+    let (start, end) = s.split_once('-')?;
+    let (start, end) = (parse(start)?, parse(end)?);
+    (start <= end).then_some(start..=end)
+}
+
+pub fn parse_pc_range(s: &str) -> Option<RangeInclusive<u16>> {
+    parse_range(s, |n| {
+        u16::from_str_radix(n.trim_start_matches("0x"), 16).ok()
+    })
+}
+
+pub fn parse_frame_range(s: &str) -> Option<RangeInclusive<u64>> {
+    parse_range(s, |n| n.parse().ok())
+}
+
+// Writes one line per instruction, taken before it executes. Instructions
+// outside `pc_range` or executed outside `frame_range` are skipped.
+pub struct TraceContext {
+    pub format: TraceFormat,
+    pub pc_range: Option<RangeInclusive<u16>>,
+    pub frame_range: Option<RangeInclusive<u64>>,
+    out: Box<dyn Write>,
+}
+
+impl TraceContext {
+    pub fn new(format: TraceFormat, out: Box<dyn Write>) -> Self {
+        TraceContext {
+            format,
+            pc_range: None,
+            frame_range: None,
+            out,
+        }
+    }
+
+    pub fn to_file(format: TraceFormat, path: &str) -> io::Result<Self> {
+        let file = File::create(path)?;
+        Ok(Self::new(format, Box::new(BufWriter::new(file))))
+    }
+
+    pub fn trace<B: Bus>(&mut self, regs: &CpuRegister, bus: &B, ticks: u64) -> io::Result<()> {
+        let pc = regs.pc;
+
+        let in_pc_range = self.pc_range.as_ref().is_none_or(|r| r.contains(&pc));
+        let in_frame_range = self
+            .frame_range
+            .as_ref()
+            .is_none_or(|r| r.contains(&bus.frame()));
+        if !in_pc_range || !in_frame_range {
+            return Ok(());
+        }
+
+        match self.format {
+            TraceFormat::Doctor => writeln!(self.out, "{}", doctor_line(regs, bus)),
+            TraceFormat::Rich => {
+                let (text, _) = disassemble(|address| bus.peek(address), pc);
+                let flags = regs.flags();
+                let flag = |set: bool, name: char| if set { name } else { '-' };
+
+                writeln!(
+                    self.out,
+                    "{:>12} {:04X}: {:<18} A:{:02X} F:{}{}{}{} BC:{:04X} DE:{:04X} HL:{:04X} SP:{:04X}",
+                    ticks,
+                    pc,
+                    text,
+                    regs.a,
+                    flag(flags.z, 'Z'),
+                    flag(flags.n, 'N'),
+                    flag(flags.h, 'H'),
+                    flag(flags.c, 'C'),
+                    regs.bc(),
+                    regs.de(),
+                    regs.hl(),
+                    regs.sp,
+                )
+            }
+        }
+    }
+
+    pub fn flush(&mut self) -> io::Result<()> {
+        self.out.flush()
+    }
+}
+
+// Where the emulator first disagreed with a reference log.
+#[derive(Debug)]
+pub struct TraceMismatch {
+    // 1-based line in the reference log.
+    pub line: u64,
+    pub expected: String,
+    pub actual: String,
+    // The instructions leading up to the mismatch, oldest first.
+    pub history: Vec<String>,
+    // 16 bytes starting 8 before PC.
+    pub pc: u16,
+    pub memory: [u8; 16],
+}
+
+impl fmt::Display for TraceMismatch {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "trace diverged at reference line {}", self.line)?;
+        writeln!(f, "  expected: {}", self.expected)?;
+        writeln!(f, "  actual:   {}", self.actual)?;
+
+        for (want, got) in self
+            .expected
+            .split_whitespace()
+            .zip(self.actual.split_whitespace())
+        {
+            if want != got {
+                writeln!(f, "    {} (expected {})", got, want)?;
+            }
+        }
+
+        writeln!(f, "last {} instructions:", self.history.len())?;
+        for line in &self.history {
+            writeln!(f, "  {}", line)?;
+        }
+
+        let start = self.pc.wrapping_sub(8);
+        write!(f, "memory at {:04X}:", start)?;
+        for (i, byte) in self.memory.iter().enumerate() {
+            let sep = if i == 8 { '>' } else { ' ' };
+            write!(f, "{}{:02X}", sep, byte)?;
+        }
+        Ok(())
+    }
+}
+
+// Checks every instruction against a Gameboy Doctor style reference log
+// and stops at the first line that differs. When the log runs out the
+// comparison just ends.
+pub struct TraceCompareContext {
+    reference: Box<dyn BufRead>,
+    line: u64,
+    depth: usize,
+    history: VecDeque<String>,
+}
+
+impl TraceCompareContext {
+    // `depth` is how many executed instructions to keep for the report.
+    pub fn new(reference: Box<dyn BufRead>, depth: usize) -> Self {
+        TraceCompareContext {
+            reference,
+            line: 0,
+            depth,
+            history: VecDeque::with_capacity(depth),
+        }
+    }
+
+    pub fn from_file(path: &str, depth: usize) -> io::Result<Self> {
+        let file = File::open(path)?;
+        Ok(Self::new(Box::new(BufReader::new(file)), depth))
+    }
+
+    pub fn compare<B: Bus>(
+        &mut self,
+        regs: &CpuRegister,
+        bus: &B,
+    ) -> io::Result<Option<TraceMismatch>> {
+        let mut expected = String::new();
+        if self.reference.read_line(&mut expected)? == 0 {
+            return Ok(None);
+        }
+        self.line += 1;
+
+        let expected = expected.trim_end();
+        let actual = doctor_line(regs, bus);
+        if expected != actual {
+            let start = regs.pc.wrapping_sub(8);
+            let mut memory = [0; 16];
+            for (i, byte) in memory.iter_mut().enumerate() {
+                *byte = bus.peek(start.wrapping_add(i as u16));
+            }
+
+            return Ok(Some(TraceMismatch {
+                line: self.line,
+                expected: expected.to_string(),
+                actual,
+                history: self.history.iter().cloned().collect(),
+                pc: regs.pc,
+                memory,
+            }));
+        }
+
+        if self.depth > 0 {
+            if self.history.len() == self.depth {
+                self.history.pop_front();
+            }
+            let (text, _) = disassemble(|address| bus.peek(address), regs.pc);
+            self.history.push_back(format!("{:04X}: {}", regs.pc, text));
+        }
+        Ok(None)
+    }
+}
@@ -0,0 +1,67 @@
+use crate::ppu::LcdMode;
+
+// Video memory, shared by the CPU and the PPU. The CPU loses access to
+// OAM while the PPU scans or draws with it (modes 2 and 3) and to VRAM
+// while it draws (mode 3), locked reads return 0xFF and writes are dropped.
+//
+// CGB has two VRAM banks, VBK (0xFF4F) picks the one the CPU sees.
+pub struct VmemContext {
+    pub vram: [u8; 0x4000],
+    pub oam: [u8; 0xA0],
+    pub vbk: u8,
+}
+
+impl Default for VmemContext {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+pub fn vram_locked(mode: LcdMode) -> bool {
+    mode == LcdMode::Xfer
+}
+
+pub fn oam_locked(mode: LcdMode) -> bool {
+    matches!(mode, LcdMode::Oam | LcdMode::Xfer)
+}
+
+impl VmemContext {
+    pub fn new() -> Self {
+        VmemContext {
+            vram: [0; 0x4000],
+            oam: [0; 0xA0],
+            vbk: 0,
+        }
+    }
+
+    pub fn vram_index(&self, address: u16) -> usize {
+        (self.vbk & 0x01) as usize * 0x2000 + (address - 0x8000) as usize
+    }
+
+    pub fn vram_read(&self, address: u16, mode: LcdMode) -> u8 {
+        if vram_locked(mode) {
+            return 0xFF;
+        }
+        self.vram[self.vram_index(address)]
+    }
+
+    pub fn vram_write(&mut self, address: u16, value: u8, mode: LcdMode) {
+        if !vram_locked(mode) {
+            let index = self.vram_index(address);
+            self.vram[index] = value;
+        }
+    }
+
+    pub fn oam_read(&self, address: u16, mode: LcdMode) -> u8 {
+        if oam_locked(mode) {
+            return 0xFF;
+        }
+        self.oam[(address - 0xFE00) as usize]
+    }
+
+    pub fn oam_write(&mut self, address: u16, value: u8, mode: LcdMode) {
+        if !oam_locked(mode) {
+            self.oam[(address - 0xFE00) as usize] = value;
+        }
+    }
+}
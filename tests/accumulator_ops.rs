@@ -0,0 +1,156 @@
+mod common;
+
+use gameboy::bus::FlatMemory;
+use gameboy::cpu::CpuContext;
+
+const Z: u8 = 0x80;
+const N: u8 = 0x40;
+const H: u8 = 0x20;
+const C: u8 = 0x10;
+
+// Runs `opcode` once for every A value and every Z/N/H/C combination,
+// checking the result against `expected(a, f) -> (a, f)`.
+fn check_all(opcode: u8, expected: impl Fn(u8, u8) -> (u8, u8)) {
+    let mut cpu = CpuContext::new(FlatMemory::new());
+
+    for a in 0..=0xFFu8 {
+        for flags in 0..16u8 {
+            let f = flags << 4;
+            cpu.regs.a = a;
+            cpu.regs.set_f(f);
+            common::run(&mut cpu, &[opcode], 1);
+
+            assert_eq!(
+                (cpu.regs.a, cpu.regs.f()),
+                expected(a, f),
+                "opcode {:02X} with A={:02X} F={:02X}",
+                opcode,
+                a,
+                f
+            );
+        }
+    }
+}
+
+fn carry(f: u8) -> u8 {
+    (f & C != 0) as u8
+}
+
+// The expected rotations are spelled out rather than using the same
+// rotate_left/rotate_right calls as the CPU.
+#[test]
+#[allow(clippy::manual_rotate)]
+fn rlca() {
+    check_all(0x07, |a, _| {
+        let res = (a << 1) | (a >> 7);
+        (res, if a & 0x80 != 0 { C } else { 0 })
+    });
+}
+
+#[test]
+#[allow(clippy::manual_rotate)]
+fn rrca() {
+    check_all(0x0F, |a, _| {
+        let res = (a >> 1) | (a << 7);
+        (res, if a & 0x01 != 0 { C } else { 0 })
+    });
+}
+
+#[test]
+fn rla() {
+    check_all(0x17, |a, f| {
+        let res = (a << 1) | carry(f);
+        (res, if a & 0x80 != 0 { C } else { 0 })
+    });
+}
+
+#[test]
+fn rra() {
+    check_all(0x1F, |a, f| {
+        let res = (a >> 1) | (carry(f) << 7);
+        (res, if a & 0x01 != 0 { C } else { 0 })
+    });
+}
+
+#[test]
+fn cpl() {
+    check_all(0x2F, |a, f| (!a, f | N | H));
+}
+
+#[test]
+fn scf() {
+    check_all(0x37, |a, f| (a, (f & Z) | C));
+}
+
+#[test]
+fn ccf() {
+    check_all(0x3F, |a, f| (a, (f & Z) | ((f & C) ^ C)));
+}
+
+// Reference DAA written in the "correct the high digit first" form, which
+// must agree with the emulator's single-adjustment implementation for
+// every input, including the non-BCD ones games never produce.
+fn daa_reference(a: u8, f: u8) -> (u8, u8) {
+    let mut res = a;
+    let mut c = f & C != 0;
+
+    if f & N == 0 {
+        if c || a > 0x99 {
+            res = res.wrapping_add(0x60);
+            c = true;
+        }
+        if f & H != 0 || (a & 0x0F) > 0x09 {
+            res = res.wrapping_add(0x06);
+        }
+    } else {
+        if c {
+            res = res.wrapping_sub(0x60);
+        }
+        if f & H != 0 {
+            res = res.wrapping_sub(0x06);
+        }
+    }
+
+    let mut flags = f & N;
+    if res == 0 {
+        flags |= Z;
+    }
+    if c {
+        flags |= C;
+    }
+    (res, flags)
+}
+
+#[test]
+fn daa_all_inputs() {
+    check_all(0x27, daa_reference);
+}
+
+fn to_bcd(n: u8) -> u8 {
+    ((n / 10) << 4) | (n % 10)
+}
+
+#[test]
+fn daa_after_bcd_add_and_sub() {
+    let mut cpu = CpuContext::new(FlatMemory::new());
+
+    for x in 0..100u8 {
+        for y in 0..100u8 {
+            // ADD A,B ; DAA
+            cpu.regs.a = to_bcd(x);
+            cpu.regs.b = to_bcd(y);
+            common::run(&mut cpu, &[0x80, 0x27], 2);
+            let sum = x as u16 + y as u16;
+            assert_eq!(cpu.regs.a, to_bcd((sum % 100) as u8), "{} + {}", x, y);
+            assert_eq!(cpu.regs.flags().c, sum >= 100, "{} + {} carry", x, y);
+
+            // SUB A,B ; DAA
+            cpu.regs.a = to_bcd(x);
+            cpu.regs.b = to_bcd(y);
+            common::run(&mut cpu, &[0x90, 0x27], 2);
+            let diff = (x as i16 - y as i16).rem_euclid(100) as u8;
+            assert_eq!(cpu.regs.a, to_bcd(diff), "{} - {}", x, y);
+            assert_eq!(cpu.regs.flags().c, x < y, "{} - {} borrow", x, y);
+        }
+    }
+}
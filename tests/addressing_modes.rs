@@ -0,0 +1,300 @@
+mod common;
+
+use common::PROGRAM_START;
+use gameboy::bus::FlatMemory;
+use gameboy::cpu::CpuContext;
+use gameboy::instructions::{AddrMode, CondType, InType, Instruction, RegType};
+
+// Executes a single instruction and returns the M-cycles it took.
+fn step(cpu: &mut CpuContext<FlatMemory>, program: &[u8]) -> u8 {
+    common::run(cpu, program, 1);
+    cpu.step_cycles
+}
+
+fn pc_advance(cpu: &CpuContext<FlatMemory>) -> u16 {
+    cpu.regs.pc.wrapping_sub(PROGRAM_START)
+}
+
+#[test]
+fn am_imp() {
+    let mut cpu = CpuContext::new(FlatMemory::new());
+
+    // NOP
+    assert_eq!(step(&mut cpu, &[0x00]), 1);
+    assert_eq!(pc_advance(&cpu), 1);
+}
+
+#[test]
+fn am_r() {
+    let mut cpu = CpuContext::new(FlatMemory::new());
+
+    // INC B
+    cpu.regs.b = 0x41;
+    assert_eq!(step(&mut cpu, &[0x04]), 1);
+    assert_eq!(pc_advance(&cpu), 1);
+    assert_eq!(cpu.regs.b, 0x42);
+
+    // JP HL
+    cpu.regs.set_hl(0xC123);
+    assert_eq!(step(&mut cpu, &[0xE9]), 1);
+    assert_eq!(cpu.regs.pc, 0xC123);
+}
+
+#[test]
+fn am_rr() {
+    let mut cpu = CpuContext::new(FlatMemory::new());
+
+    // LD B,C
+    cpu.regs.c = 0x5A;
+    assert_eq!(step(&mut cpu, &[0x41]), 1);
+    assert_eq!(pc_advance(&cpu), 1);
+    assert_eq!(cpu.regs.b, 0x5A);
+
+    // LD SP,HL
+    cpu.regs.set_hl(0xDFF0);
+    assert_eq!(step(&mut cpu, &[0xF9]), 2);
+    assert_eq!(cpu.regs.sp, 0xDFF0);
+}
+
+#[test]
+fn am_r_d8() {
+    let mut cpu = CpuContext::new(FlatMemory::new());
+
+    // LD D,$99
+    assert_eq!(step(&mut cpu, &[0x16, 0x99]), 2);
+    assert_eq!(pc_advance(&cpu), 2);
+    assert_eq!(cpu.regs.d, 0x99);
+}
+
+#[test]
+fn am_r_d16() {
+    let mut cpu = CpuContext::new(FlatMemory::new());
+
+    // LD BC,$1234
+    assert_eq!(step(&mut cpu, &[0x01, 0x34, 0x12]), 3);
+    assert_eq!(pc_advance(&cpu), 3);
+    assert_eq!(cpu.regs.bc(), 0x1234);
+}
+
+#[test]
+fn am_mr_r() {
+    let mut cpu = CpuContext::new(FlatMemory::new());
+
+    // LD [HL],E
+    cpu.regs.set_hl(0xD000);
+    cpu.regs.e = 0x77;
+    assert_eq!(step(&mut cpu, &[0x73]), 2);
+    assert_eq!(pc_advance(&cpu), 1);
+    assert_eq!(cpu.bus_read(0xD000), 0x77);
+
+    // LD [C],A
+    cpu.regs.c = 0x80;
+    cpu.regs.a = 0x3C;
+    assert_eq!(step(&mut cpu, &[0xE2]), 2);
+    assert_eq!(cpu.bus_read(0xFF80), 0x3C);
+}
+
+#[test]
+fn am_r_mr() {
+    let mut cpu = CpuContext::new(FlatMemory::new());
+
+    // LD A,[DE]
+    cpu.bus_write(0xD010, 0xAB);
+    cpu.regs.set_de(0xD010);
+    assert_eq!(step(&mut cpu, &[0x1A]), 2);
+    assert_eq!(pc_advance(&cpu), 1);
+    assert_eq!(cpu.regs.a, 0xAB);
+
+    // LD A,[C]
+    cpu.bus_write(0xFF81, 0xCD);
+    cpu.regs.c = 0x81;
+    assert_eq!(step(&mut cpu, &[0xF2]), 2);
+    assert_eq!(cpu.regs.a, 0xCD);
+}
+
+#[test]
+fn am_r_hli_and_r_hld() {
+    let mut cpu = CpuContext::new(FlatMemory::new());
+
+    cpu.bus_write(0xD020, 0x11);
+    cpu.bus_write(0xD021, 0x22);
+
+    // LD A,[HL+]
+    cpu.regs.set_hl(0xD020);
+    assert_eq!(step(&mut cpu, &[0x2A]), 2);
+    assert_eq!(pc_advance(&cpu), 1);
+    assert_eq!(cpu.regs.a, 0x11);
+    assert_eq!(cpu.regs.hl(), 0xD021);
+
+    // LD A,[HL-]
+    assert_eq!(step(&mut cpu, &[0x3A]), 2);
+    assert_eq!(cpu.regs.a, 0x22);
+    assert_eq!(cpu.regs.hl(), 0xD020);
+}
+
+#[test]
+fn am_hli_r_and_hld_r() {
+    let mut cpu = CpuContext::new(FlatMemory::new());
+
+    // LD [HL+],A
+    cpu.regs.set_hl(0xD030);
+    cpu.regs.a = 0x5E;
+    assert_eq!(step(&mut cpu, &[0x22]), 2);
+    assert_eq!(pc_advance(&cpu), 1);
+    assert_eq!(cpu.bus_read(0xD030), 0x5E);
+    assert_eq!(cpu.regs.hl(), 0xD031);
+
+    // LD [HL-],A
+    cpu.regs.a = 0x6F;
+    assert_eq!(step(&mut cpu, &[0x32]), 2);
+    assert_eq!(cpu.bus_read(0xD031), 0x6F);
+    assert_eq!(cpu.regs.hl(), 0xD030);
+}
+
+#[test]
+fn am_r_a8() {
+    let mut cpu = CpuContext::new(FlatMemory::new());
+
+    // LDH A,[$FF90]
+    cpu.bus_write(0xFF90, 0x42);
+    assert_eq!(step(&mut cpu, &[0xF0, 0x90]), 3);
+    assert_eq!(pc_advance(&cpu), 2);
+    assert_eq!(cpu.regs.a, 0x42);
+}
+
+#[test]
+fn am_a8_r() {
+    let mut cpu = CpuContext::new(FlatMemory::new());
+
+    // LDH [$FF91],A
+    cpu.regs.a = 0x24;
+    assert_eq!(step(&mut cpu, &[0xE0, 0x91]), 3);
+    assert_eq!(pc_advance(&cpu), 2);
+    assert_eq!(cpu.bus_read(0xFF91), 0x24);
+}
+
+#[test]
+fn am_hl_spr() {
+    let mut cpu = CpuContext::new(FlatMemory::new());
+
+    // LD HL,SP-2
+    cpu.regs.sp = 0xDFF0;
+    assert_eq!(step(&mut cpu, &[0xF8, 0xFE]), 3);
+    assert_eq!(pc_advance(&cpu), 2);
+    assert_eq!(cpu.regs.hl(), 0xDFEE);
+    // 0xF0 + 0xFE carries out of the low byte but not the low nibble.
+    assert_eq!(cpu.regs.f(), 0x10);
+}
+
+#[test]
+fn am_d16() {
+    let mut cpu = CpuContext::new(FlatMemory::new());
+
+    // JP $C200
+    assert_eq!(step(&mut cpu, &[0xC3, 0x00, 0xC2]), 4);
+    assert_eq!(cpu.regs.pc, 0xC200);
+
+    // JP NZ,$C200 not taken
+    cpu.regs.set_f(0x80);
+    assert_eq!(step(&mut cpu, &[0xC2, 0x00, 0xC2]), 3);
+    assert_eq!(pc_advance(&cpu), 3);
+}
+
+#[test]
+fn am_d8() {
+    let mut cpu = CpuContext::new(FlatMemory::new());
+
+    // JR -2 jumps back onto itself.
+    assert_eq!(step(&mut cpu, &[0x18, 0xFE]), 3);
+    assert_eq!(cpu.regs.pc, PROGRAM_START);
+
+    // JR +4
+    assert_eq!(step(&mut cpu, &[0x18, 0x04]), 3);
+    assert_eq!(cpu.regs.pc, PROGRAM_START + 6);
+
+    // JR Z,+4 not taken
+    cpu.regs.set_f(0x00);
+    assert_eq!(step(&mut cpu, &[0x28, 0x04]), 2);
+    assert_eq!(pc_advance(&cpu), 2);
+}
+
+#[test]
+fn am_d16_r() {
+    // No opcode decodes to this mode, drive fetch_data with a synthetic
+    // descriptor instead.
+    static LD_D16_A: Instruction = Instruction {
+        type_in: InType::InLd,
+        mode: AddrMode::AmD16r,
+        reg_1: RegType::RtNone,
+        reg_2: RegType::RtA,
+        cond: CondType::CtNone,
+        param: 0,
+    };
+
+    let mut cpu = CpuContext::new(FlatMemory::new());
+
+    cpu.bus_write(PROGRAM_START, 0x40);
+    cpu.bus_write(PROGRAM_START + 1, 0xD0);
+    cpu.regs.pc = PROGRAM_START;
+    cpu.regs.a = 0x99;
+    cpu.cur_inst = &LD_D16_A;
+    cpu.fetch_data();
+
+    assert_eq!(pc_advance(&cpu), 2);
+    assert_eq!(cpu.step_cycles, 2);
+    assert!(cpu.dest_is_mem);
+    assert_eq!(cpu.mem_dest, 0xD040);
+    assert_eq!(cpu.fetched_data, 0x99);
+}
+
+#[test]
+fn am_mr_d8() {
+    let mut cpu = CpuContext::new(FlatMemory::new());
+
+    // LD [HL],$5C
+    cpu.regs.set_hl(0xD050);
+    assert_eq!(step(&mut cpu, &[0x36, 0x5C]), 3);
+    assert_eq!(pc_advance(&cpu), 2);
+    assert_eq!(cpu.bus_read(0xD050), 0x5C);
+}
+
+#[test]
+fn am_mr() {
+    let mut cpu = CpuContext::new(FlatMemory::new());
+
+    // INC [HL]
+    cpu.regs.set_hl(0xD060);
+    cpu.bus_write(0xD060, 0x0F);
+    assert_eq!(step(&mut cpu, &[0x34]), 3);
+    assert_eq!(pc_advance(&cpu), 1);
+    assert_eq!(cpu.bus_read(0xD060), 0x10);
+}
+
+#[test]
+fn am_a16_r() {
+    let mut cpu = CpuContext::new(FlatMemory::new());
+
+    // LD [$D070],A
+    cpu.regs.a = 0x81;
+    assert_eq!(step(&mut cpu, &[0xEA, 0x70, 0xD0]), 4);
+    assert_eq!(pc_advance(&cpu), 3);
+    assert_eq!(cpu.bus_read(0xD070), 0x81);
+
+    // LD [$D072],SP
+    cpu.regs.sp = 0xBEEF;
+    assert_eq!(step(&mut cpu, &[0x08, 0x72, 0xD0]), 5);
+    assert_eq!(pc_advance(&cpu), 3);
+    assert_eq!(cpu.bus_read(0xD072), 0xEF);
+    assert_eq!(cpu.bus_read(0xD073), 0xBE);
+}
+
+#[test]
+fn am_r_a16() {
+    let mut cpu = CpuContext::new(FlatMemory::new());
+
+    // LD A,[$D080]
+    cpu.bus_write(0xD080, 0x3E);
+    assert_eq!(step(&mut cpu, &[0xFA, 0x80, 0xD0]), 4);
+    assert_eq!(pc_advance(&cpu), 3);
+    assert_eq!(cpu.regs.a, 0x3E);
+}
@@ -0,0 +1,147 @@
+mod common;
+
+use gameboy::bus::FlatMemory;
+use gameboy::cpu::CpuContext;
+
+const Z: u8 = 0x80;
+const N: u8 = 0x40;
+const H: u8 = 0x20;
+const C: u8 = 0x10;
+
+// Runs `opcode` (an 8-bit ALU op with B as the operand) for every A, B and
+// carry in, checking the result against `expected(a, b, carry) -> (a, f)`.
+fn check_all(opcode: u8, expected: impl Fn(u8, u8, u8) -> (u8, u8)) {
+    let mut cpu = CpuContext::new(FlatMemory::new());
+
+    for a in 0..=0xFFu8 {
+        for b in 0..=0xFFu8 {
+            for carry in 0..2u8 {
+                cpu.regs.a = a;
+                cpu.regs.b = b;
+                cpu.regs.set_f(carry << 4);
+                common::run(&mut cpu, &[opcode], 1);
+
+                assert_eq!(
+                    (cpu.regs.a, cpu.regs.f()),
+                    expected(a, b, carry),
+                    "opcode {:02X} with A={:02X} B={:02X} carry={}",
+                    opcode,
+                    a,
+                    b,
+                    carry
+                );
+            }
+        }
+    }
+}
+
+fn flags(res: u8, n: bool, h: bool, c: bool) -> u8 {
+    let mut f = 0;
+    if res == 0 {
+        f |= Z;
+    }
+    if n {
+        f |= N;
+    }
+    if h {
+        f |= H;
+    }
+    if c {
+        f |= C;
+    }
+    f
+}
+
+fn add(a: u8, b: u8, carry: u8) -> (u8, u8) {
+    let res = a.wrapping_add(b).wrapping_add(carry);
+    let h = (a & 0x0F) + (b & 0x0F) + carry > 0x0F;
+    let c = a as u16 + b as u16 + carry as u16 > 0xFF;
+    (res, flags(res, false, h, c))
+}
+
+fn sub(a: u8, b: u8, carry: u8) -> (u8, u8) {
+    let res = a.wrapping_sub(b).wrapping_sub(carry);
+    let h = (a & 0x0F) < (b & 0x0F) + carry;
+    let c = (a as u16) < b as u16 + carry as u16;
+    (res, flags(res, true, h, c))
+}
+
+#[test]
+fn add_a_r() {
+    check_all(0x80, |a, b, _| add(a, b, 0));
+}
+
+#[test]
+fn adc_a_r() {
+    check_all(0x88, add);
+}
+
+#[test]
+fn sub_r() {
+    check_all(0x90, |a, b, _| sub(a, b, 0));
+}
+
+#[test]
+fn sbc_a_r() {
+    check_all(0x98, sub);
+}
+
+#[test]
+fn and_r() {
+    check_all(0xA0, |a, b, _| (a & b, flags(a & b, false, true, false)));
+}
+
+#[test]
+fn xor_r() {
+    check_all(0xA8, |a, b, _| (a ^ b, flags(a ^ b, false, false, false)));
+}
+
+#[test]
+fn or_r() {
+    check_all(0xB0, |a, b, _| (a | b, flags(a | b, false, false, false)));
+}
+
+#[test]
+fn cp_r() {
+    check_all(0xB8, |a, b, _| (a, sub(a, b, 0).1));
+}
+
+// (A, B, carry in, opcode) -> (A, F) at the nibble and byte boundaries.
+#[test]
+fn carry_edges() {
+    let cases: [(u8, u8, u8, u8, u8, u8); 10] = [
+        // ADD: half carry only, full carry, and wrap to zero.
+        (0x0F, 0x01, 0, 0x80, 0x10, H),
+        (0xF0, 0x10, 0, 0x80, 0x00, Z | C),
+        (0xFF, 0x01, 0, 0x80, 0x00, Z | H | C),
+        // ADC: the carry in alone crosses the nibble and the byte.
+        (0x0F, 0x00, 1, 0x88, 0x10, H),
+        (0xFF, 0x00, 1, 0x88, 0x00, Z | H | C),
+        // SUB: borrow from the high nibble, and a full borrow.
+        (0x10, 0x01, 0, 0x90, 0x0F, N | H),
+        (0x00, 0x01, 0, 0x90, 0xFF, N | H | C),
+        // SBC: the carry in alone borrows.
+        (0x10, 0x00, 1, 0x98, 0x0F, N | H),
+        (0x00, 0x00, 1, 0x98, 0xFF, N | H | C),
+        // CP leaves A alone.
+        (0x3C, 0x3C, 0, 0xB8, 0x3C, Z | N),
+    ];
+
+    let mut cpu = CpuContext::new(FlatMemory::new());
+    for (a, b, carry, opcode, res, f) in cases {
+        cpu.regs.a = a;
+        cpu.regs.b = b;
+        cpu.regs.set_f(carry << 4);
+        common::run(&mut cpu, &[opcode], 1);
+
+        assert_eq!(
+            (cpu.regs.a, cpu.regs.f()),
+            (res, f),
+            "opcode {:02X} with A={:02X} B={:02X} carry={}",
+            opcode,
+            a,
+            b,
+            carry
+        );
+    }
+}
@@ -0,0 +1,54 @@
+use gameboy::bus::{Bus, BusContext};
+use gameboy::cart::CartContext;
+use gameboy::model::Model;
+
+const NR52: u16 = 0xFF26;
+
+// One frame sequencer step, 8192 T-cycles.
+const STEP_CYCLES: usize = 2048;
+
+#[test]
+fn length_counter_silences_channel() {
+    let mut cart = CartContext::new();
+    let mut bus = BusContext::new(&mut cart, Model::Dmg);
+
+    // Square 1 with the DAC on, two steps of length left, length enabled.
+    bus.write(0xFF12, 0xF0);
+    bus.write(0xFF11, 0x3E);
+    bus.write(0xFF14, 0xC0);
+    assert_eq!(bus.read(NR52), 0xF1);
+
+    // Length is clocked on steps 0 and 2, the third step uses it up.
+    for _ in 0..2 * STEP_CYCLES + 16 {
+        bus.tick();
+    }
+    assert_eq!(bus.read(NR52), 0xF1);
+    for _ in 0..STEP_CYCLES {
+        bus.tick();
+    }
+    assert_eq!(bus.read(NR52), 0xF0);
+}
+
+#[test]
+fn dac_off_and_power_off() {
+    let mut cart = CartContext::new();
+    let mut bus = BusContext::new(&mut cart, Model::Dmg);
+
+    // Wave channel, triggered and then its DAC switched off.
+    bus.write(0xFF1A, 0x80);
+    bus.write(0xFF1E, 0x80);
+    assert_eq!(bus.read(NR52), 0xF4);
+    bus.write(0xFF1A, 0x00);
+    assert_eq!(bus.read(NR52), 0xF0);
+
+    // Powering off clears the registers and ignores writes until it's
+    // back on.
+    bus.write(0xFF12, 0xF0);
+    bus.write(NR52, 0x00);
+    assert_eq!(bus.read(NR52), 0x70);
+    assert_eq!(bus.read(0xFF12), 0x00);
+    bus.write(0xFF12, 0xF0);
+    assert_eq!(bus.read(0xFF12), 0x00);
+    bus.write(NR52, 0x80);
+    assert_eq!(bus.read(NR52), 0xF0);
+}
@@ -0,0 +1,173 @@
+mod common;
+
+use gameboy::bus::FlatMemory;
+use gameboy::cpu::CpuContext;
+
+const Z: u8 = 0x80;
+const N: u8 = 0x40;
+const H: u8 = 0x20;
+const C: u8 = 0x10;
+
+// ADD HL,rr: Z is kept, H from bit 11 and C from bit 15.
+fn add_hl(hl: u16, rr: u16, f: u8) -> (u16, u8) {
+    let mut flags = f & Z;
+    if (hl & 0x0FFF) + (rr & 0x0FFF) > 0x0FFF {
+        flags |= H;
+    }
+    if hl as u32 + rr as u32 > 0xFFFF {
+        flags |= C;
+    }
+    (hl.wrapping_add(rr), flags)
+}
+
+#[test]
+fn add_hl_rr() {
+    let mut cpu = CpuContext::new(FlatMemory::new());
+
+    for hl in (0..=0xFFFFu16).step_by(0x0101) {
+        for rr in (0..=0xFFFFu16).step_by(0x0F0F) {
+            for f in [0x00, Z | N | H | C] {
+                // ADD HL,BC ; ADD HL,DE ; ADD HL,SP
+                for opcode in [0x09, 0x19, 0x39] {
+                    cpu.regs.set_hl(hl);
+                    cpu.regs.set_bc(rr);
+                    cpu.regs.set_de(rr);
+                    cpu.regs.sp = rr;
+                    cpu.regs.set_f(f);
+                    common::run(&mut cpu, &[opcode], 1);
+
+                    assert_eq!(
+                        (cpu.regs.hl(), cpu.regs.f()),
+                        add_hl(hl, rr, f),
+                        "opcode {:02X} with HL={:04X} rr={:04X} F={:02X}",
+                        opcode,
+                        hl,
+                        rr,
+                        f
+                    );
+                    assert_eq!(cpu.step_cycles, 2);
+                }
+            }
+        }
+    }
+}
+
+// (HL, rr, F in) -> (HL, F out) at the bit 11 and bit 15 boundaries.
+#[test]
+fn add_hl_edges() {
+    let cases: [(u16, u16, u8, u16, u8); 6] = [
+        (0x0FFF, 0x0001, 0x00, 0x1000, H),
+        // A carry out of bit 7 doesn't count.
+        (0x00FF, 0x0001, 0x00, 0x0100, 0x00),
+        (0xF000, 0x1000, 0x00, 0x0000, C),
+        // A zero result doesn't set Z, a set Z is kept.
+        (0xFFFF, 0x0001, Z | N, 0x0000, Z | H | C),
+        (0x0001, 0x0001, Z, 0x0002, Z),
+        (0x8800, 0x8800, 0x00, 0x1000, H | C),
+    ];
+
+    let mut cpu = CpuContext::new(FlatMemory::new());
+    for (hl, rr, f, res, res_f) in cases {
+        cpu.regs.set_hl(hl);
+        cpu.regs.set_bc(rr);
+        cpu.regs.set_f(f);
+
+        // ADD HL,BC
+        common::run(&mut cpu, &[0x09], 1);
+        assert_eq!(
+            (cpu.regs.hl(), cpu.regs.f()),
+            (res, res_f),
+            "HL={:04X} BC={:04X} F={:02X}",
+            hl,
+            rr,
+            f
+        );
+    }
+
+    // ADD HL,HL
+    cpu.regs.set_hl(0x8800);
+    cpu.regs.set_f(0);
+    common::run(&mut cpu, &[0x29], 1);
+    assert_eq!((cpu.regs.hl(), cpu.regs.f()), (0x1000, H | C));
+}
+
+// (SP, e8) -> (SP + e8, F). The flags come from adding e8 to the low byte
+// of SP as unsigned, whatever its sign, and Z and N are always clear.
+const SP_E8_CASES: [(u16, u8, u16, u8); 6] = [
+    (0x00FF, 0x01, 0x0100, H | C),
+    (0x000F, 0x01, 0x0010, H),
+    (0x0001, 0xFF, 0x0000, H | C),
+    (0x0000, 0xFF, 0xFFFF, 0x00),
+    (0xD00F, 0xF1, 0xD000, H | C),
+    (0xD0F0, 0x80, 0xD070, C),
+];
+
+#[test]
+fn add_sp_e8() {
+    let mut cpu = CpuContext::new(FlatMemory::new());
+
+    for (sp, e8, res, res_f) in SP_E8_CASES {
+        cpu.regs.sp = sp;
+        cpu.regs.set_f(Z | N);
+
+        // ADD SP,e8
+        common::run(&mut cpu, &[0xE8, e8], 1);
+        assert_eq!(
+            (cpu.regs.sp, cpu.regs.f()),
+            (res, res_f),
+            "SP={:04X} e8={:02X}",
+            sp,
+            e8
+        );
+        assert_eq!(cpu.step_cycles, 4);
+    }
+}
+
+#[test]
+fn ld_hl_sp_e8() {
+    let mut cpu = CpuContext::new(FlatMemory::new());
+
+    for (sp, e8, res, res_f) in SP_E8_CASES {
+        cpu.regs.sp = sp;
+        cpu.regs.set_f(Z | N);
+
+        // LD HL,SP+e8
+        common::run(&mut cpu, &[0xF8, e8], 1);
+        assert_eq!(
+            (cpu.regs.hl(), cpu.regs.f()),
+            (res, res_f),
+            "SP={:04X} e8={:02X}",
+            sp,
+            e8
+        );
+        assert_eq!(cpu.regs.sp, sp);
+        assert_eq!(cpu.step_cycles, 3);
+    }
+}
+
+#[test]
+fn inc_dec_rr() {
+    let mut cpu = CpuContext::new(FlatMemory::new());
+
+    for flags in 0..16u8 {
+        let f = flags << 4;
+
+        // INC BC ; DEC DE ; INC HL ; DEC SP, wrapping without flags.
+        cpu.regs.set_bc(0xFFFF);
+        cpu.regs.set_de(0x0000);
+        cpu.regs.set_hl(0x00FF);
+        cpu.regs.sp = 0x1000;
+        cpu.regs.set_f(f);
+        common::run(&mut cpu, &[0x03, 0x1B, 0x23, 0x3B], 0);
+
+        for _ in 0..4 {
+            cpu.cpu_step().unwrap();
+            assert_eq!(cpu.step_cycles, 2);
+            assert_eq!(cpu.regs.f(), f);
+        }
+        assert_eq!(cpu.regs.bc(), 0x0000);
+        assert_eq!(cpu.regs.de(), 0xFFFF);
+        assert_eq!(cpu.regs.hl(), 0x0100);
+        assert_eq!(cpu.regs.sp, 0x0FFF);
+    }
+}
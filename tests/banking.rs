@@ -0,0 +1,75 @@
+use gameboy::bus::{Bus, BusContext};
+use gameboy::cart::CartContext;
+use gameboy::model::Model;
+use gameboy::ppu::LcdMode;
+
+const VBK: u16 = 0xFF4F;
+const SVBK: u16 = 0xFF70;
+
+#[test]
+fn wram_banks() {
+    let mut cart = CartContext::new();
+    let mut bus = BusContext::new(&mut cart, Model::Cgb);
+
+    // Tag 0xD000 in every bank, SVBK 0 selects bank 1.
+    for bank in 1..8 {
+        bus.write(SVBK, bank);
+        bus.write(0xD000, bank * 0x11);
+    }
+    bus.write(SVBK, 0);
+    assert_eq!(bus.read(0xD000), 0x11);
+
+    for bank in 1..8 {
+        bus.write(SVBK, bank);
+        assert_eq!(bus.read(0xD000), bank * 0x11, "bank {}", bank);
+        assert_eq!(bus.read(SVBK), 0xF8 | bank);
+    }
+
+    // Bank 0 doesn't move, and only the low 3 bits count.
+    bus.write(0xC000, 0x99);
+    bus.write(SVBK, 0xFA);
+    assert_eq!(bus.read(SVBK), 0xFA);
+    assert_eq!(bus.read(0xD000), 0x22);
+    assert_eq!(bus.read(0xC000), 0x99);
+}
+
+#[test]
+fn wram_banks_dmg() {
+    let mut cart = CartContext::new();
+    let mut bus = BusContext::new(&mut cart, Model::Dmg);
+
+    bus.write(0xD000, 0x11);
+    bus.write(SVBK, 2);
+    assert_eq!(bus.read(SVBK), 0xFF);
+    assert_eq!(bus.read(0xD000), 0x11);
+}
+
+#[test]
+fn vram_banks() {
+    let mut cart = CartContext::new();
+    let mut bus = BusContext::new(&mut cart, Model::Cgb);
+    bus.ppu.mode = LcdMode::HBlank;
+
+    bus.write(0x8000, 0x11);
+    bus.write(VBK, 0xFF);
+    assert_eq!(bus.read(VBK), 0xFF);
+    assert_eq!(bus.read(0x8000), 0x00);
+    bus.write(0x8000, 0x22);
+
+    bus.write(VBK, 0x00);
+    assert_eq!(bus.read(VBK), 0xFE);
+    assert_eq!(bus.read(0x8000), 0x11);
+}
+
+#[test]
+fn vram_banks_dmg() {
+    let mut cart = CartContext::new();
+    let mut bus = BusContext::new(&mut cart, Model::Dmg);
+    bus.ppu.mode = LcdMode::HBlank;
+
+    // There's only the one bank.
+    bus.write(0x8000, 0x11);
+    bus.write(VBK, 0x01);
+    assert_eq!(bus.read(VBK), 0xFF);
+    assert_eq!(bus.read(0x8000), 0x11);
+}
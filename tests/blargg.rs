@@ -0,0 +1,74 @@
+// Blargg's test ROMs, laid out as in the gb-test-roms collection under
+// $BLARGG_ROMS, or tests/roms/blargg by default. The ROMs aren't vendored
+// so these are ignored by default, run them with
+// `cargo test --test blargg -- --ignored`.
+
+use std::path::PathBuf;
+
+use gameboy::headless::{run_test_rom, TestStatus, MAX_CYCLES};
+use gameboy::model::Model;
+
+const CPU_INSTRS: [&str; 11] = [
+    "01-special.gb",
+    "02-interrupts.gb",
+    "03-op sp,hl.gb",
+    "04-op r,imm.gb",
+    "05-op rp.gb",
+    "06-ld r,r.gb",
+    "07-jr,jp,call,ret,rst.gb",
+    "08-misc instrs.gb",
+    "09-op r,r.gb",
+    "10-bit ops.gb",
+    "11-op a,(hl).gb",
+];
+
+fn blargg(rom: &str) {
+    let dir = std::env::var_os("BLARGG_ROMS")
+        .map(PathBuf::from)
+        .unwrap_or_else(|| PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("tests/roms/blargg"));
+    let path = dir.join(rom);
+    assert!(path.exists(), "{} not found", path.display());
+
+    let report = run_test_rom(path.to_str().unwrap(), Model::Dmg, MAX_CYCLES).unwrap();
+    assert_eq!(
+        report.status,
+        TestStatus::Passed,
+        "{} after {} M-cycles\nserial:\n{}\nmemory:\n{}",
+        rom,
+        report.cycles,
+        report.serial,
+        report.memory
+    );
+}
+
+#[test]
+#[ignore = "needs Blargg's ROMs"]
+fn cpu_instrs() {
+    for rom in CPU_INSTRS {
+        blargg(&format!("cpu_instrs/individual/{}", rom));
+    }
+}
+
+#[test]
+#[ignore = "needs Blargg's ROMs"]
+fn instr_timing() {
+    blargg("instr_timing/instr_timing.gb");
+}
+
+#[test]
+#[ignore = "needs Blargg's ROMs"]
+fn mem_timing() {
+    blargg("mem_timing/mem_timing.gb");
+}
+
+#[test]
+#[ignore = "needs Blargg's ROMs"]
+fn dmg_sound() {
+    blargg("dmg_sound/dmg_sound.gb");
+}
+
+#[test]
+#[ignore = "needs Blargg's ROMs"]
+fn halt_bug() {
+    blargg("halt_bug.gb");
+}
@@ -0,0 +1,166 @@
+mod common;
+
+use gameboy::bus::{Bus, FlatMemory};
+use gameboy::cpu::CpuContext;
+
+const Z: u8 = 0x80;
+const H: u8 = 0x20;
+const C: u8 = 0x10;
+
+const HL_ADDR: u16 = 0xD000;
+
+// Runs CB `opcode` (with B as the operand) for every B value and every
+// Z/N/H/C combination, checking the result against
+// `expected(b, f) -> (b, f)`.
+fn check_all(opcode: u8, expected: impl Fn(u8, u8) -> (u8, u8)) {
+    let mut cpu = CpuContext::new(FlatMemory::new());
+
+    for b in 0..=0xFFu8 {
+        for flags in 0..16u8 {
+            let f = flags << 4;
+            cpu.regs.b = b;
+            cpu.regs.set_f(f);
+            common::run(&mut cpu, &[0xCB, opcode], 1);
+
+            assert_eq!(
+                (cpu.regs.b, cpu.regs.f()),
+                expected(b, f),
+                "CB {:02X} with B={:02X} F={:02X}",
+                opcode,
+                b,
+                f
+            );
+            assert_eq!(cpu.step_cycles, 2, "CB {:02X}", opcode);
+        }
+    }
+}
+
+fn carry(f: u8) -> u8 {
+    (f & C != 0) as u8
+}
+
+// Z from the result, C from the bit shifted out, N and H clear.
+fn shift_flags(res: u8, out: bool) -> u8 {
+    let mut f = 0;
+    if res == 0 {
+        f |= Z;
+    }
+    if out {
+        f |= C;
+    }
+    f
+}
+
+#[test]
+fn rlc() {
+    check_all(0x00, |b, _| {
+        let res = b.rotate_left(1);
+        (res, shift_flags(res, b & 0x80 != 0))
+    });
+}
+
+#[test]
+fn rrc() {
+    check_all(0x08, |b, _| {
+        let res = b.rotate_right(1);
+        (res, shift_flags(res, b & 0x01 != 0))
+    });
+}
+
+#[test]
+fn rl() {
+    check_all(0x10, |b, f| {
+        let res = (b << 1) | carry(f);
+        (res, shift_flags(res, b & 0x80 != 0))
+    });
+}
+
+#[test]
+fn rr() {
+    check_all(0x18, |b, f| {
+        let res = (b >> 1) | (carry(f) << 7);
+        (res, shift_flags(res, b & 0x01 != 0))
+    });
+}
+
+#[test]
+fn sla() {
+    check_all(0x20, |b, _| {
+        let res = b << 1;
+        (res, shift_flags(res, b & 0x80 != 0))
+    });
+}
+
+#[test]
+fn sra() {
+    check_all(0x28, |b, _| {
+        let res = (b >> 1) | (b & 0x80);
+        (res, shift_flags(res, b & 0x01 != 0))
+    });
+}
+
+#[test]
+fn swap() {
+    check_all(0x30, |b, _| {
+        let res = b.rotate_right(4);
+        (res, shift_flags(res, false))
+    });
+}
+
+#[test]
+fn srl() {
+    check_all(0x38, |b, _| {
+        let res = b >> 1;
+        (res, shift_flags(res, b & 0x01 != 0))
+    });
+}
+
+#[test]
+fn bit() {
+    for n in 0..8 {
+        check_all(0x40 | n << 3, |b, f| {
+            let z = if b & (1 << n) == 0 { Z } else { 0 };
+            (b, z | H | (f & C))
+        });
+    }
+}
+
+#[test]
+fn res_set() {
+    for n in 0..8 {
+        check_all(0x80 | n << 3, |b, f| (b & !(1 << n), f));
+        check_all(0xC0 | n << 3, |b, f| (b | (1 << n), f));
+    }
+}
+
+// (opcode, [HL] before, F before) -> ([HL] after, F after, M-cycles)
+#[test]
+fn hl_indirect() {
+    let cases: [(u8, u8, u8, u8, u8, u8); 8] = [
+        (0x06, 0x85, 0x00, 0x0B, C, 4),      // RLC [HL]
+        (0x16, 0x80, 0x00, 0x00, Z | C, 4),  // RL [HL]
+        (0x2E, 0x81, 0x00, 0xC0, C, 4),      // SRA [HL]
+        (0x36, 0x00, C, 0x00, Z, 4),         // SWAP [HL]
+        (0x46, 0xFE, C, 0xFE, Z | H | C, 3), // BIT 0,[HL]
+        (0x7E, 0x80, 0x00, 0x80, H, 3),      // BIT 7,[HL]
+        (0x9E, 0xFF, Z | C, 0xF7, Z | C, 4), // RES 3,[HL]
+        (0xEE, 0x00, 0x00, 0x20, 0x00, 4),   // SET 5,[HL]
+    ];
+
+    let mut cpu = CpuContext::new(FlatMemory::new());
+    for (opcode, value, f, res, res_f, cycles) in cases {
+        cpu.regs.set_hl(HL_ADDR);
+        cpu.regs.set_f(f);
+        cpu.bus.write(HL_ADDR, value);
+        common::run(&mut cpu, &[0xCB, opcode], 1);
+
+        assert_eq!(
+            (cpu.bus.peek(HL_ADDR), cpu.regs.f(), cpu.step_cycles),
+            (res, res_f, cycles),
+            "CB {:02X} with [HL]={:02X} F={:02X}",
+            opcode,
+            value,
+            f
+        );
+    }
+}
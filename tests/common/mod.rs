@@ -0,0 +1,17 @@
+use gameboy::bus::Bus;
+use gameboy::cpu::CpuContext;
+
+pub const PROGRAM_START: u16 = 0xC000;
+
+// Places `program` in work RAM, points PC at it and executes `steps`
+// instructions.
+pub fn run<B: Bus>(cpu: &mut CpuContext<B>, program: &[u8], steps: usize) {
+    for (i, byte) in program.iter().enumerate() {
+        cpu.bus_write(PROGRAM_START + i as u16, *byte);
+    }
+
+    cpu.regs.pc = PROGRAM_START;
+    for _ in 0..steps {
+        cpu.cpu_step().unwrap();
+    }
+}
@@ -0,0 +1,46 @@
+use gameboy::disasm::disassemble;
+
+fn disasm(bytes: &[u8], addr: u16) -> (String, u16) {
+    disassemble(|a| bytes.get(a.wrapping_sub(addr) as usize).copied().unwrap_or(0), addr)
+}
+
+#[test]
+fn renders_rgbds_syntax() {
+    let cases: &[(&[u8], &str, u16)] = &[
+        (&[0x00], "NOP", 1),
+        (&[0x2A], "LD A,[HL+]", 1),
+        (&[0x32], "LD [HL-],A", 1),
+        (&[0x01, 0x34, 0x12], "LD BC,$1234", 3),
+        (&[0x08, 0x00, 0xC0], "LD [$C000],SP", 3),
+        (&[0xE0, 0x40], "LDH [$FF40],A", 2),
+        (&[0xF0, 0x44], "LDH A,[$FF44]", 2),
+        (&[0xE2], "LDH [C],A", 1),
+        (&[0xF2], "LDH A,[C]", 1),
+        (&[0x36, 0x7F], "LD [HL],$7F", 2),
+        (&[0xFE, 0x90], "CP A,$90", 2),
+        (&[0x86], "ADD A,[HL]", 1),
+        (&[0x34], "INC [HL]", 1),
+        (&[0xE8, 0xFE], "ADD SP,-$02", 2),
+        (&[0xF8, 0x05], "LD HL,SP+$05", 2),
+        (&[0xC3, 0x50, 0x01], "JP $0150", 3),
+        (&[0xC4, 0x00, 0x40], "CALL NZ,$4000", 3),
+        (&[0xC8], "RET Z", 1),
+        (&[0xFF], "RST $38", 1),
+        (&[0xF5], "PUSH AF", 1),
+        (&[0x10, 0x00], "STOP", 2),
+        (&[0xD3], "DB $D3", 1),
+        (&[0xCB, 0x7C], "BIT 7,H", 2),
+        (&[0xCB, 0x86], "RES 0,[HL]", 2),
+        (&[0xCB, 0x37], "SWAP A", 2),
+    ];
+
+    for (bytes, text, len) in cases {
+        assert_eq!(disasm(bytes, 0x0100), (text.to_string(), *len), "{:02X?}", bytes);
+    }
+}
+
+#[test]
+fn resolves_relative_jumps() {
+    assert_eq!(disasm(&[0x20, 0x4E], 0x0100), ("JR NZ,$0150".to_string(), 2));
+    assert_eq!(disasm(&[0x18, 0xFE], 0x0200), ("JR $0200".to_string(), 2));
+}
@@ -0,0 +1,76 @@
+use gameboy::bus::{Bus, BusContext};
+use gameboy::cart::CartContext;
+use gameboy::model::Model;
+
+const DMA: u16 = 0xFF46;
+
+// Work RAM at 0xC100 and 0xC200 holds two different OAM images.
+fn bus_with_sources(cart: &mut CartContext) -> BusContext<'_> {
+    let mut bus = BusContext::new(cart, Model::Dmg);
+    for i in 0..0xA0 {
+        bus.write(0xC100 + i, i as u8);
+        bus.write(0xC200 + i, !(i as u8));
+    }
+    bus
+}
+
+fn tick(bus: &mut BusContext, cycles: usize) {
+    for _ in 0..cycles {
+        bus.tick();
+    }
+}
+
+#[test]
+fn transfer() {
+    let mut cart = CartContext::new();
+    let mut bus = bus_with_sources(&mut cart);
+
+    // The cycle of the write and the setup cycle leave the bus alone.
+    bus.write(DMA, 0xC1);
+    tick(&mut bus, 1);
+    assert!(!bus.dma.dma_transferring());
+    assert_eq!(bus.read(0xC105), 0x05);
+    tick(&mut bus, 1);
+    assert!(bus.dma.dma_transferring());
+    assert_eq!(bus.ppu.vmem.oam[0], 0x00);
+
+    // A byte per M-cycle, CPU reads below 0xFF00 see the byte being copied
+    // and writes are dropped.
+    tick(&mut bus, 0x10);
+    assert_eq!(bus.ppu.vmem.oam[0x0F], 0x0F);
+    assert_eq!(bus.ppu.vmem.oam[0x10], 0x00);
+    assert_eq!(bus.read(0xC150), 0x0F);
+    assert_eq!(bus.read(0x0000), 0x0F);
+    bus.write(0xC000, 0x12);
+    bus.write(0xFF80, 0x34);
+    assert_eq!(bus.read(0xFF80), 0x34);
+
+    // 160 M-cycles in all.
+    tick(&mut bus, 0xA0 - 0x11);
+    assert!(bus.dma.dma_transferring());
+    tick(&mut bus, 1);
+    assert!(!bus.dma.dma_transferring());
+    assert!((0..0xA0).all(|i| bus.ppu.vmem.oam[i] == i as u8));
+    assert_eq!(bus.read(0xC000), 0x00);
+}
+
+#[test]
+fn restart() {
+    let mut cart = CartContext::new();
+    let mut bus = bus_with_sources(&mut cart);
+
+    bus.write(DMA, 0xC1);
+    tick(&mut bus, 2 + 10);
+
+    // The old transfer keeps going until the new one is set up.
+    bus.write(DMA, 0xC2);
+    tick(&mut bus, 2);
+    assert!(bus.dma.dma_transferring());
+    assert_eq!(bus.ppu.vmem.oam[11], 11);
+    assert_eq!(bus.ppu.vmem.oam[12], 0);
+
+    // Then it starts over from the new page.
+    tick(&mut bus, 0xA0);
+    assert!(!bus.dma.dma_transferring());
+    assert!((0..0xA0).all(|i| bus.ppu.vmem.oam[i] == !(i as u8)));
+}
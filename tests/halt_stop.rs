@@ -0,0 +1,161 @@
+mod common;
+
+use common::PROGRAM_START;
+use gameboy::bus::{Bus, BusContext, FlatMemory};
+use gameboy::cart::CartContext;
+use gameboy::cpu::CpuContext;
+use gameboy::interrupts::InterruptType;
+use gameboy::joypad::Button;
+use gameboy::model::Model;
+
+const IF: u16 = 0xFF0F;
+const IE: u16 = 0xFFFF;
+
+#[test]
+fn halt_wakes_without_ime() {
+    let mut cpu = CpuContext::new(FlatMemory::new());
+    cpu.bus.write(IE, InterruptType::Timer as u8);
+
+    // HALT ; NOP
+    common::run(&mut cpu, &[0x76, 0x00], 1);
+    assert!(cpu.halted);
+
+    cpu.cpu_step().unwrap();
+    assert!(cpu.halted);
+    assert_eq!(cpu.regs.pc, PROGRAM_START + 1);
+
+    // A pending interrupt wakes the CPU even with IME clear, and it carries
+    // on after HALT instead of dispatching.
+    cpu.bus.write(IF, InterruptType::Timer as u8);
+    cpu.cpu_step().unwrap();
+    assert!(!cpu.halted);
+    assert_eq!(cpu.regs.pc, PROGRAM_START + 1);
+
+    cpu.cpu_step().unwrap();
+    assert_eq!(cpu.regs.pc, PROGRAM_START + 2);
+    assert_eq!(cpu.bus.int_flags(), InterruptType::Timer as u8);
+}
+
+#[test]
+fn halt_bug() {
+    let mut cpu = CpuContext::new(FlatMemory::new());
+    cpu.bus.write(IF, InterruptType::Timer as u8);
+    cpu.bus.write(IE, InterruptType::Timer as u8);
+    cpu.regs.a = 0;
+
+    // HALT ; INC A, with IME clear and an interrupt pending HALT doesn't
+    // halt and the byte after it is read twice.
+    common::run(&mut cpu, &[0x76, 0x3C], 1);
+    assert!(!cpu.halted);
+
+    cpu.cpu_step().unwrap();
+    assert_eq!(cpu.regs.pc, PROGRAM_START + 1);
+    cpu.cpu_step().unwrap();
+    assert_eq!(cpu.regs.pc, PROGRAM_START + 2);
+    assert_eq!(cpu.regs.a, 2);
+}
+
+#[test]
+fn stop_resets_div_and_wakes_on_joypad() {
+    let mut cart = CartContext::new();
+    let mut cpu = CpuContext::new(BusContext::new(&mut cart, Model::Dmg));
+    cpu.bus.timer.div = 0xAB00;
+
+    // Select the action buttons so a press can raise the interrupt.
+    cpu.bus.write(0xFF00, 0x10);
+
+    // STOP ; NOP
+    common::run(&mut cpu, &[0x10, 0x00, 0x00], 1);
+    assert!(cpu.stopped);
+    assert_eq!(cpu.bus.peek(0xFF04), 0);
+
+    // Nothing runs while stopped.
+    let div = cpu.bus.timer.div;
+    for _ in 0..100 {
+        cpu.cpu_step().unwrap();
+    }
+    assert!(cpu.stopped);
+    assert_eq!(cpu.bus.timer.div, div);
+    assert_eq!(cpu.regs.pc, PROGRAM_START + 2);
+
+    cpu.bus.joypad_set(Button::A, true);
+    cpu.cpu_step().unwrap();
+    assert!(!cpu.stopped);
+
+    cpu.cpu_step().unwrap();
+    assert_eq!(cpu.regs.pc, PROGRAM_START + 3);
+}
+
+#[test]
+fn stop_ignores_pending_joypad_request() {
+    let mut cart = CartContext::new();
+    let mut cpu = CpuContext::new(BusContext::new(&mut cart, Model::Dmg));
+    cpu.bus.write(0xFF00, 0x10);
+
+    // A press before STOP leaves IF.4 latched, it doesn't wake the CPU.
+    cpu.bus.joypad_set(Button::A, true);
+    cpu.bus.joypad_set(Button::A, false);
+    assert_eq!(cpu.bus.int_flags(), InterruptType::Joypad as u8);
+
+    // STOP ; NOP
+    common::run(&mut cpu, &[0x10, 0x00, 0x00], 1);
+    for _ in 0..10 {
+        cpu.cpu_step().unwrap();
+    }
+    assert!(cpu.stopped);
+
+    // Neither does a button in the group that isn't selected.
+    cpu.bus.write(0xFF00, 0x20);
+    cpu.bus.joypad_set(Button::B, true);
+    cpu.cpu_step().unwrap();
+    assert!(cpu.stopped);
+
+    cpu.bus.joypad_set(Button::Right, true);
+    cpu.cpu_step().unwrap();
+    assert!(!cpu.stopped);
+}
+
+#[test]
+fn stop_switches_speed() {
+    let mut cart = CartContext::new();
+    let mut cpu = CpuContext::new(BusContext::new(&mut cart, Model::Cgb));
+    cpu.bus.timer.div = 0xAB00;
+
+    // LD A,1 ; LDH (0x4D),A ; STOP ; NOP
+    common::run(&mut cpu, &[0x3E, 0x01, 0xE0, 0x4D, 0x10, 0x00, 0x00], 3);
+    assert!(!cpu.stopped);
+    assert_eq!(cpu.bus.read(0xFF4D), 0xFE);
+
+    // The CPU and DIV are held for 2050 M-cycles.
+    let mut cycles = 0;
+    while cpu.bus.stalled() {
+        cycles += cpu.cpu_step().unwrap() as u32;
+    }
+    assert_eq!(cycles, 2050);
+    assert_eq!(cpu.bus.timer.div, 0);
+    assert_eq!(cpu.regs.pc, PROGRAM_START + 6);
+
+    // A line is now 228 M-cycles, DIV still counts 4 per M-cycle.
+    let ly = cpu.bus.ppu.ly;
+    for _ in 0..228 {
+        cpu.bus.tick();
+    }
+    assert_eq!(cpu.bus.ppu.ly, ly + 1);
+    assert_eq!(cpu.bus.timer.div, 228 * 4);
+
+    // Switching again goes back to normal speed.
+    cpu.bus.write(0xFF4D, 0x01);
+    assert!(cpu.bus.speed_switch());
+    assert_eq!(cpu.bus.read(0xFF4D), 0x7E);
+}
+
+#[test]
+fn stop_without_switch_on_dmg() {
+    let mut cart = CartContext::new();
+    let mut cpu = CpuContext::new(BusContext::new(&mut cart, Model::Dmg));
+
+    // LD A,1 ; LDH (0x4D),A ; STOP
+    common::run(&mut cpu, &[0x3E, 0x01, 0xE0, 0x4D, 0x10, 0x00], 3);
+    assert!(cpu.stopped);
+    assert!(!cpu.bus.stalled());
+}
@@ -0,0 +1,134 @@
+use gameboy::bus::{Bus, BusContext};
+use gameboy::cart::CartContext;
+use gameboy::model::Model;
+use gameboy::ppu::LcdMode;
+
+const HDMA5: u16 = 0xFF55;
+
+// CGB bus with a 0x40 byte pattern at 0xC000, set up to copy it to 0x8000.
+fn bus_with_source(cart: &mut CartContext) -> BusContext<'_> {
+    let mut bus = BusContext::new(cart, Model::Cgb);
+    for i in 0..0x40 {
+        bus.write(0xC000 + i, 0x80 | i as u8);
+    }
+
+    bus.write(0xFF51, 0xC0);
+    bus.write(0xFF52, 0x00);
+    bus.write(0xFF53, 0x00);
+    bus.write(0xFF54, 0x00);
+    bus
+}
+
+fn copied(bus: &BusContext) -> usize {
+    (0..0x40).take_while(|&i| bus.ppu.vmem.vram[i] == 0x80 | i as u8).count()
+}
+
+// Ticks while the CPU is held, returning the M-cycles it waited.
+fn stall(bus: &mut BusContext) -> u32 {
+    let mut cycles = 0;
+    while bus.stalled() {
+        bus.tick();
+        cycles += 1;
+    }
+    cycles
+}
+
+// Ticks up to the start of the next HBlank.
+fn next_hblank(bus: &mut BusContext) {
+    while bus.ppu.mode == LcdMode::HBlank {
+        bus.tick();
+    }
+    while bus.ppu.mode != LcdMode::HBlank {
+        bus.tick();
+    }
+}
+
+#[test]
+fn general_purpose() {
+    let mut cart = CartContext::new();
+    let mut bus = bus_with_source(&mut cart);
+
+    // Two blocks, 8 M-cycles each with the CPU held throughout.
+    bus.write(HDMA5, 0x01);
+    assert_eq!(stall(&mut bus), 16);
+    assert_eq!(copied(&bus), 0x20);
+    assert_eq!(bus.read(HDMA5), 0xFF);
+}
+
+#[test]
+fn general_purpose_double_speed() {
+    let mut cart = CartContext::new();
+    let mut bus = bus_with_source(&mut cart);
+
+    bus.write(0xFF4D, 0x01);
+    assert!(bus.speed_switch());
+    stall(&mut bus);
+
+    // Half the bytes per M-cycle, the same time in real terms.
+    bus.write(HDMA5, 0x01);
+    assert_eq!(stall(&mut bus), 32);
+    assert_eq!(copied(&bus), 0x20);
+}
+
+#[test]
+fn hblank() {
+    let mut cart = CartContext::new();
+    let mut bus = bus_with_source(&mut cart);
+
+    // Nothing moves until the PPU reaches HBlank, then one block a line.
+    bus.write(HDMA5, 0x82);
+    assert_eq!(bus.read(HDMA5), 0x02);
+    assert!(!bus.stalled());
+
+    next_hblank(&mut bus);
+    assert_eq!(stall(&mut bus), 8);
+    assert_eq!(copied(&bus), 0x10);
+    assert_eq!(bus.read(HDMA5), 0x01);
+
+    next_hblank(&mut bus);
+    stall(&mut bus);
+    assert_eq!(copied(&bus), 0x20);
+    assert_eq!(bus.read(HDMA5), 0x00);
+
+    // The last block ends it.
+    next_hblank(&mut bus);
+    stall(&mut bus);
+    assert_eq!(copied(&bus), 0x30);
+    assert_eq!(bus.read(HDMA5), 0xFF);
+}
+
+#[test]
+fn hblank_cancel() {
+    let mut cart = CartContext::new();
+    let mut bus = bus_with_source(&mut cart);
+
+    bus.write(HDMA5, 0x83);
+    next_hblank(&mut bus);
+    stall(&mut bus);
+
+    // Clearing bit 7 stops it, bit 7 reads back set with the blocks left.
+    bus.write(HDMA5, 0x00);
+    assert_eq!(bus.read(HDMA5), 0x82);
+
+    next_hblank(&mut bus);
+    assert!(!bus.stalled());
+    assert_eq!(copied(&bus), 0x10);
+}
+
+#[test]
+fn hblank_lcd_off() {
+    let mut cart = CartContext::new();
+    let mut bus = bus_with_source(&mut cart);
+    bus.write(0xFF40, 0x00);
+
+    // The first block goes straight away, the rest wait for the LCD.
+    bus.write(HDMA5, 0x81);
+    assert_eq!(stall(&mut bus), 8);
+    assert_eq!(copied(&bus), 0x10);
+
+    for _ in 0..1000 {
+        bus.tick();
+    }
+    assert_eq!(copied(&bus), 0x10);
+    assert_eq!(bus.read(HDMA5), 0x00);
+}
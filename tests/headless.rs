@@ -0,0 +1,33 @@
+use std::fs;
+
+use gameboy::headless::{run_mooneye_rom, run_test_rom, TestStatus};
+use gameboy::model::Model;
+
+// A 32 KiB ROM that runs STOP at the entry point, nothing ever wakes it.
+fn stop_rom(name: &str) -> String {
+    let mut rom = vec![0; 0x8000];
+    rom[0x100] = 0x10;
+    rom[0x101] = 0x00;
+
+    let path = std::env::temp_dir().join(format!("{}-{}.gb", name, std::process::id()));
+    fs::write(&path, rom).unwrap();
+    path.to_str().unwrap().to_string()
+}
+
+#[test]
+fn stopped_rom_times_out() {
+    let path = stop_rom("blargg-stop");
+    let report = run_test_rom(&path, Model::Dmg, 10_000).unwrap();
+    fs::remove_file(&path).unwrap();
+
+    assert_eq!(report.status, TestStatus::TimedOut);
+}
+
+#[test]
+fn stopped_mooneye_rom_times_out() {
+    let path = stop_rom("mooneye-stop");
+    let status = run_mooneye_rom(&path, Model::Dmg, 10_000).unwrap();
+    fs::remove_file(&path).unwrap();
+
+    assert_eq!(status, TestStatus::TimedOut);
+}
@@ -0,0 +1,108 @@
+mod common;
+
+use common::PROGRAM_START;
+use gameboy::bus::{Bus, FlatMemory};
+use gameboy::cpu::CpuContext;
+use gameboy::interrupts::InterruptType;
+
+const IF: u16 = 0xFF0F;
+const IE: u16 = 0xFFFF;
+const STACK_TOP: u16 = 0xD000;
+
+fn cpu_with(int_flags: u8, int_enable: u8, ime: bool) -> CpuContext<FlatMemory> {
+    let mut cpu = CpuContext::new(FlatMemory::new());
+    cpu.regs.sp = STACK_TOP;
+    cpu.int_master_enable = ime;
+    cpu.bus.write(IF, int_flags);
+    cpu.bus.write(IE, int_enable);
+    cpu
+}
+
+fn return_address(cpu: &CpuContext<FlatMemory>) -> u16 {
+    u16::from_le_bytes([cpu.bus.peek(cpu.regs.sp), cpu.bus.peek(cpu.regs.sp + 1)])
+}
+
+#[test]
+fn masked_by_ie() {
+    let mut cpu = cpu_with(InterruptType::Timer as u8, InterruptType::Vblank as u8, true);
+
+    // NOP
+    common::run(&mut cpu, &[0x00], 1);
+    assert_eq!(cpu.regs.pc, PROGRAM_START + 1);
+    assert_eq!(cpu.bus.int_flags(), InterruptType::Timer as u8);
+    assert!(cpu.int_master_enable);
+}
+
+#[test]
+fn masked_by_ime() {
+    let mut cpu = cpu_with(0x1F, 0x1F, false);
+
+    // NOP
+    common::run(&mut cpu, &[0x00], 1);
+    assert_eq!(cpu.regs.pc, PROGRAM_START + 1);
+    assert_eq!(cpu.bus.int_flags(), 0x1F);
+}
+
+#[test]
+fn dispatch() {
+    let mut cpu = cpu_with(InterruptType::Serial as u8, 0x1F, true);
+
+    // NOP, then 5 M-cycles to push PC and jump to the vector.
+    common::run(&mut cpu, &[0x00], 1);
+    assert_eq!(cpu.step_cycles, 1 + 5);
+    assert_eq!(cpu.regs.pc, 0x58);
+    assert_eq!(cpu.regs.sp, STACK_TOP - 2);
+    assert_eq!(return_address(&cpu), PROGRAM_START + 1);
+
+    // Servicing clears the request and IME.
+    assert_eq!(cpu.bus.int_flags(), 0);
+    assert!(!cpu.int_master_enable);
+}
+
+#[test]
+fn priority() {
+    let vectors = [
+        (InterruptType::Vblank, 0x40),
+        (InterruptType::LcdStat, 0x48),
+        (InterruptType::Timer, 0x50),
+        (InterruptType::Serial, 0x58),
+        (InterruptType::Joypad, 0x60),
+    ];
+
+    // Each request is only serviced once every lower bit has been.
+    let mut cpu = cpu_with(0x1F, 0x1F, true);
+    let mut remaining = 0x1F;
+    for (it, vector) in vectors {
+        cpu.int_master_enable = true;
+        common::run(&mut cpu, &[0x00], 1);
+
+        remaining &= !(it as u8);
+        assert_eq!(cpu.regs.pc, vector, "{:?}", it);
+        assert_eq!(cpu.bus.int_flags(), remaining, "{:?}", it);
+    }
+}
+
+#[test]
+fn ei_delay() {
+    let mut cpu = cpu_with(InterruptType::Vblank as u8, 0x1F, false);
+
+    // EI ; NOP ; NOP
+    common::run(&mut cpu, &[0xFB, 0x00, 0x00], 1);
+    assert_eq!(cpu.regs.pc, PROGRAM_START + 1);
+    assert!(cpu.int_master_enable);
+
+    // The instruction after EI still runs before the dispatch.
+    cpu.cpu_step().unwrap();
+    assert_eq!(cpu.regs.pc, 0x40);
+    assert_eq!(return_address(&cpu), PROGRAM_START + 2);
+}
+
+#[test]
+fn ei_di_cancels() {
+    let mut cpu = cpu_with(InterruptType::Vblank as u8, 0x1F, false);
+
+    // EI ; DI ; NOP
+    common::run(&mut cpu, &[0xFB, 0xF3, 0x00], 3);
+    assert_eq!(cpu.regs.pc, PROGRAM_START + 3);
+    assert!(!cpu.int_master_enable);
+}
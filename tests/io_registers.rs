@@ -0,0 +1,57 @@
+use gameboy::bus::{Bus, BusContext};
+use gameboy::cart::CartContext;
+use gameboy::model::Model;
+
+// Register, then what it reads back after writing 0x00 and 0xFF on DMG and
+// on CGB. Unused bits read as 1, and CGB registers don't exist on DMG.
+const READBACK: [(u16, [u8; 2], [u8; 2]); 16] = [
+    (0xFF02, [0x7E, 0xFF], [0x7E, 0xFF]), // SC
+    (0xFF03, [0xFF, 0xFF], [0xFF, 0xFF]),
+    (0xFF0F, [0xE0, 0xFF], [0xE0, 0xFF]), // IF
+    (0xFF41, [0x84, 0xFC], [0x84, 0xFC]), // STAT, LY == LYC in HBlank
+    (0xFF4D, [0xFF, 0xFF], [0x7E, 0x7F]), // KEY1
+    (0xFF4F, [0xFF, 0xFF], [0xFE, 0xFF]), // VBK
+    (0xFF50, [0xFF, 0xFF], [0xFF, 0xFF]),
+    (0xFF56, [0xFF, 0xFF], [0x3C, 0xFD]), // RP
+    (0xFF68, [0xFF, 0xFF], [0x40, 0xFF]), // BCPS
+    (0xFF6A, [0xFF, 0xFF], [0x40, 0xFF]), // OCPS
+    (0xFF6C, [0xFF, 0xFF], [0xFE, 0xFF]), // OPRI
+    (0xFF70, [0xFF, 0xFF], [0xF8, 0xFF]), // SVBK
+    (0xFF72, [0xFF, 0xFF], [0x00, 0xFF]),
+    (0xFF75, [0xFF, 0xFF], [0x8F, 0xFF]),
+    (0xFF76, [0xFF, 0xFF], [0x00, 0x00]), // PCM12
+    (0xFF7F, [0xFF, 0xFF], [0xFF, 0xFF]),
+];
+
+fn check_readback(model: Model) {
+    let mut cart = CartContext::new();
+    let mut bus = BusContext::new(&mut cart, model);
+
+    // LCD off, so STAT reads HBlank on line 0.
+    bus.write(0xFF40, 0x00);
+
+    for (address, dmg, cgb) in READBACK {
+        let expected = if model == Model::Cgb { cgb } else { dmg };
+        for (value, expected) in [0x00, 0xFF].into_iter().zip(expected) {
+            bus.write(address, value);
+            assert_eq!(
+                bus.read(address),
+                expected,
+                "{:?} {:04X} after writing {:02X}",
+                model,
+                address,
+                value
+            );
+        }
+    }
+}
+
+#[test]
+fn readback_dmg() {
+    check_readback(Model::Dmg);
+}
+
+#[test]
+fn readback_cgb() {
+    check_readback(Model::Cgb);
+}
@@ -0,0 +1,82 @@
+use gameboy::bus::{Bus, BusContext};
+use gameboy::cart::CartContext;
+use gameboy::model::Model;
+use gameboy::ppu::LcdMode;
+
+#[test]
+fn echo_ram() {
+    let mut cart = CartContext::new();
+    let mut bus = BusContext::new(&mut cart, Model::Dmg);
+
+    bus.write(0xE123, 0x5A);
+    assert_eq!(bus.read(0xC123), 0x5A);
+    bus.write(0xDDFF, 0xA5);
+    assert_eq!(bus.read(0xFDFF), 0xA5);
+}
+
+#[test]
+fn unusable_region() {
+    let mut cart = CartContext::new();
+    let mut bus = BusContext::new(&mut cart, Model::Dmg);
+    bus.ppu.mode = LcdMode::HBlank;
+
+    // Writes go nowhere and DMG reads 0.
+    bus.write(0xFEA0, 0x12);
+    assert_eq!(bus.read(0xFEA0), 0x00);
+    assert_eq!(bus.read(0xFEFF), 0x00);
+
+    let mut cart = CartContext::new();
+    let mut bus = BusContext::new(&mut cart, Model::Cgb);
+    bus.ppu.mode = LcdMode::VBlank;
+
+    // CGB repeats the high nibble of the address.
+    assert_eq!(bus.read(0xFEA0), 0xAA);
+    assert_eq!(bus.read(0xFEB7), 0xBB);
+    assert_eq!(bus.read(0xFEFF), 0xFF);
+    assert_eq!(bus.read(0xFEC0), 0xCC);
+
+    // While the PPU holds OAM the whole region reads 0xFF.
+    for mode in [LcdMode::Oam, LcdMode::Xfer] {
+        bus.ppu.mode = mode;
+        assert_eq!(bus.read(0xFEC0), 0xFF);
+    }
+}
+
+#[test]
+fn vram_oam_locked() {
+    let mut cart = CartContext::new();
+    let mut bus = BusContext::new(&mut cart, Model::Dmg);
+
+    bus.ppu.mode = LcdMode::HBlank;
+    bus.write(0x8000, 0x11);
+    bus.write(0xFE00, 0x22);
+
+    // (mode, VRAM locked, OAM locked)
+    let cases = [
+        (LcdMode::HBlank, false, false),
+        (LcdMode::VBlank, false, false),
+        (LcdMode::Oam, false, true),
+        (LcdMode::Xfer, true, true),
+    ];
+
+    for (mode, vram_locked, oam_locked) in cases {
+        bus.ppu.mode = mode;
+        bus.write(0x8000, 0x33);
+        bus.write(0xFE00, 0x44);
+
+        let vram = if vram_locked { 0xFF } else { 0x33 };
+        let oam = if oam_locked { 0xFF } else { 0x44 };
+        assert_eq!(bus.read(0x8000), vram, "VRAM in {:?}", mode);
+        assert_eq!(bus.read(0xFE00), oam, "OAM in {:?}", mode);
+
+        // Locked writes are dropped.
+        bus.ppu.mode = LcdMode::HBlank;
+        let vram = if vram_locked { 0x11 } else { 0x33 };
+        let oam = if oam_locked { 0x22 } else { 0x44 };
+        assert_eq!(bus.read(0x8000), vram, "VRAM write in {:?}", mode);
+        assert_eq!(bus.read(0xFE00), oam, "OAM write in {:?}", mode);
+
+        bus.write(0x8000, 0x11);
+        bus.write(0xFE00, 0x22);
+    }
+}
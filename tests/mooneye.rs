@@ -0,0 +1,82 @@
+// Mooneye's test ROMs under $MOONEYE_ROMS, or tests/roms/mooneye by
+// default (e.g. the acceptance/ directory of a mooneye-test-suite build).
+// Every ROM runs on each model its name targets and the results are
+// printed as a matrix. The ROMs aren't vendored so the matrix is ignored by
+// default, run it with `cargo test --test mooneye -- --ignored`.
+
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use gameboy::headless::{mooneye_models, run_mooneye_rom, TestStatus};
+use gameboy::model::Model;
+
+const MAX_CYCLES: u64 = 30 * 1_048_576;
+const MODELS: [Model; 2] = [Model::Dmg, Model::Cgb];
+
+fn find_roms(dir: &Path, roms: &mut Vec<PathBuf>) {
+    let Ok(entries) = fs::read_dir(dir) else {
+        return;
+    };
+
+    for path in entries.filter_map(|e| e.ok().map(|e| e.path())) {
+        if path.is_dir() {
+            find_roms(&path, roms);
+        } else if path.extension().is_some_and(|ext| ext == "gb") {
+            roms.push(path);
+        }
+    }
+}
+
+#[test]
+fn model_selection() {
+    assert_eq!(mooneye_models("add_sp_e_timing"), vec![Model::Dmg, Model::Cgb]);
+    assert_eq!(mooneye_models("boot_regs-dmgABC"), vec![Model::Dmg]);
+    assert_eq!(mooneye_models("boot_regs-cgb"), vec![Model::Cgb]);
+    assert_eq!(mooneye_models("di_timing-GS"), vec![Model::Dmg]);
+    assert_eq!(mooneye_models("boot_div-dmgABCmgb"), vec![Model::Dmg]);
+    assert_eq!(mooneye_models("boot_hwio-S"), Vec::<Model>::new());
+    assert_eq!(mooneye_models("boot_regs-dmg0"), Vec::<Model>::new());
+}
+
+#[test]
+#[ignore = "needs Mooneye's ROMs"]
+fn mooneye_matrix() {
+    let dir = std::env::var_os("MOONEYE_ROMS")
+        .map(PathBuf::from)
+        .unwrap_or_else(|| PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("tests/roms/mooneye"));
+
+    let mut roms = Vec::new();
+    find_roms(&dir, &mut roms);
+    assert!(!roms.is_empty(), "no Mooneye ROMs in {}", dir.display());
+    roms.sort();
+
+    let mut failed = Vec::new();
+    println!("{:<50} {:<8} {:<8}", "test", "DMG", "CGB");
+    for rom in &roms {
+        let name = rom.strip_prefix(&dir).unwrap_or(rom).display().to_string();
+        let models = mooneye_models(&rom.file_stem().unwrap().to_string_lossy());
+
+        let cells: Vec<String> = MODELS
+            .iter()
+            .map(|model| {
+                if !models.contains(model) {
+                    return "-".to_string();
+                }
+
+                let result = run_mooneye_rom(rom.to_str().unwrap(), *model, MAX_CYCLES);
+                let cell = match &result {
+                    Ok(status) => format!("{:?}", status),
+                    Err(err) => format!("Error: {}", err),
+                };
+                if !matches!(result, Ok(TestStatus::Passed)) {
+                    failed.push(format!("{} ({:?}): {}", name, model, cell));
+                }
+                cell
+            })
+            .collect();
+
+        println!("{:<50} {:<8} {:<8}", name, cells[0], cells[1]);
+    }
+
+    assert!(failed.is_empty(), "failing:\n{}", failed.join("\n"));
+}
@@ -0,0 +1,64 @@
+mod common;
+
+use gameboy::bus::{Bus, FlatMemory};
+use gameboy::cpu::{CpuContext, CpuRegister, Flags};
+
+#[test]
+fn af_masks_f() {
+    let mut regs = CpuRegister::new();
+
+    regs.set_af(0x12FF);
+    assert_eq!(regs.a, 0x12);
+    assert_eq!(regs.f(), 0xF0);
+    assert_eq!(regs.af(), 0x12F0);
+
+    regs.set_f(0x5A);
+    assert_eq!(regs.f(), 0x50);
+}
+
+#[test]
+fn pop_af_masks_f() {
+    let mut cpu = CpuContext::new(FlatMemory::new());
+    cpu.regs.sp = 0xD000;
+    cpu.bus.write(0xD000, 0xFF);
+    cpu.bus.write(0xD001, 0x34);
+
+    // POP AF
+    common::run(&mut cpu, &[0xF1], 1);
+    assert_eq!(cpu.regs.af(), 0x34F0);
+    assert_eq!(cpu.regs.f() & 0x0F, 0);
+}
+
+#[test]
+fn pairs_round_trip() {
+    let mut regs = CpuRegister::new();
+
+    for value in [0x0000, 0x00FF, 0xFF00, 0x1234, 0xFFFF] {
+        regs.set_bc(value);
+        regs.set_de(value.rotate_left(4));
+        regs.set_hl(!value);
+
+        assert_eq!(regs.bc(), value);
+        assert_eq!(regs.de(), value.rotate_left(4));
+        assert_eq!(regs.hl(), !value);
+        assert_eq!(u16::from_be_bytes([regs.b, regs.c]), value);
+        assert_eq!(u16::from_be_bytes([regs.h, regs.l]), !value);
+    }
+}
+
+#[test]
+fn flags_round_trip() {
+    for f in 0..=0xFFu8 {
+        let flags = Flags::from(f);
+        assert_eq!(
+            flags,
+            Flags::new(f & 0x80 != 0, f & 0x40 != 0, f & 0x20 != 0, f & 0x10 != 0)
+        );
+        assert_eq!(u8::from(flags), f & 0xF0);
+    }
+
+    let mut regs = CpuRegister::new();
+    regs.set_flags(Flags::new(true, false, true, false));
+    assert_eq!(regs.f(), 0xA0);
+    assert_eq!(regs.flags(), Flags::new(true, false, true, false));
+}
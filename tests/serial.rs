@@ -0,0 +1,79 @@
+use std::cell::Cell;
+use std::rc::Rc;
+
+use gameboy::interrupts::InterruptType;
+use gameboy::serial::{SerialCapture, SerialContext, SerialDevice};
+
+const SB: u16 = 0xFF01;
+const SC: u16 = 0xFF02;
+
+// Runs `ticks` T-cycles, returning the interrupts requested along the way.
+fn tick(serial: &mut SerialContext, ticks: u32) -> u8 {
+    (0..ticks).fold(0, |int_flags, _| int_flags | serial.serial_tick())
+}
+
+#[test]
+fn internal_clock() {
+    let capture = SerialCapture::default();
+    let mut serial = SerialContext::new();
+    serial.serial_connect(Box::new(capture.clone()));
+
+    serial.serial_write(SB, 0x5A);
+    serial.serial_write(SC, 0x81);
+    assert_eq!(capture.output.borrow().as_slice(), &[0x5A]);
+    assert_eq!(serial.serial_read(SC), 0xFF);
+
+    // One bit every 512 T-cycles, the capture shifts in 1s.
+    assert_eq!(tick(&mut serial, 511), 0);
+    assert_eq!(serial.serial_read(SB), 0x5A);
+    assert_eq!(tick(&mut serial, 1), 0);
+    assert_eq!(serial.serial_read(SB), 0xB5);
+
+    // The 8th bit finishes the transfer, clears SC bit 7 and interrupts.
+    assert_eq!(tick(&mut serial, 512 * 6), 0);
+    assert_eq!(serial.serial_read(SC) & 0x80, 0x80);
+    assert_eq!(tick(&mut serial, 512), InterruptType::Serial as u8);
+    assert_eq!(serial.serial_read(SB), 0xFF);
+    assert_eq!(serial.serial_read(SC), 0x7F);
+
+    // Nothing more happens until the next transfer.
+    assert_eq!(tick(&mut serial, 4096), 0);
+    assert_eq!(capture.output.borrow().len(), 1);
+}
+
+// Sends 0x42 back, clocking a bit whenever `clock` is set.
+struct ExternalDevice {
+    clock: Rc<Cell<bool>>,
+}
+
+impl SerialDevice for ExternalDevice {
+    fn serial_transfer(&mut self, _out: u8) -> u8 {
+        0x42
+    }
+
+    fn serial_external_clock(&mut self) -> bool {
+        self.clock.get()
+    }
+}
+
+#[test]
+fn external_clock() {
+    let clock = Rc::new(Cell::new(false));
+    let mut serial = SerialContext::new();
+    serial.serial_connect(Box::new(ExternalDevice { clock: clock.clone() }));
+
+    serial.serial_write(SB, 0x00);
+    serial.serial_write(SC, 0x80);
+
+    // Without the other end clocking, the transfer never moves.
+    assert_eq!(tick(&mut serial, 4096), 0);
+    assert_eq!(serial.serial_read(SC), 0xFE);
+
+    // One bit per clocked T-cycle.
+    clock.set(true);
+    assert_eq!(tick(&mut serial, 7), 0);
+    assert_eq!(serial.serial_read(SB), 0x21);
+    assert_eq!(tick(&mut serial, 1), InterruptType::Serial as u8);
+    assert_eq!(serial.serial_read(SB), 0x42);
+    assert_eq!(serial.serial_read(SC), 0x7E);
+}
@@ -0,0 +1,278 @@
+// Runs the single-step SM83 JSON test vectors (one file per opcode, e.g.
+// `3e.json` or `cb 7c.json`) from $SM83_TESTS, or tests/sm83 by default.
+// The vectors aren't vendored so the test is ignored by default, run it
+// with `cargo test --test sm83_json -- --ignored`.
+//
+// Each case gives the registers and the RAM bytes the instruction touches
+// before and after it runs, along with one entry per M-cycle of bus
+// activity: `[address, value, "r-m"]` for a read, `"-wm"` for a write and
+// `"---"` (or null) when the bus is idle. The opcode sits at the initial PC.
+
+use std::fmt;
+use std::fs;
+use std::path::PathBuf;
+
+use gameboy::bus::{Bus, FlatMemory};
+use gameboy::cpu::CpuContext;
+use serde_json::Value;
+
+// A read or write seen on the bus.
+#[derive(Clone, Copy, PartialEq)]
+enum Access {
+    Read(u16, u8),
+    Write(u16, u8),
+}
+
+impl fmt::Display for Access {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Access::Read(address, value) => write!(f, "read {:04X}={:02X}", address, value),
+            Access::Write(address, value) => write!(f, "write {:04X}={:02X}", address, value),
+        }
+    }
+}
+
+// Flat memory that logs the accesses made in every M-cycle. The interrupt
+// lines are held low, so RAM at 0xFF0F/0xFFFF can't trigger a dispatch in
+// the middle of a vector.
+struct TestBus {
+    mem: FlatMemory,
+    // Accesses per completed M-cycle, and those of the cycle in progress.
+    cycles: Vec<Vec<Access>>,
+    current: Vec<Access>,
+}
+
+impl TestBus {
+    fn new() -> Self {
+        TestBus {
+            mem: FlatMemory::new(),
+            cycles: Vec::new(),
+            current: Vec::new(),
+        }
+    }
+}
+
+impl Bus for TestBus {
+    fn read(&mut self, address: u16) -> u8 {
+        let value = self.mem.read(address);
+        self.current.push(Access::Read(address, value));
+        value
+    }
+
+    fn write(&mut self, address: u16, value: u8) {
+        self.mem.write(address, value);
+        self.current.push(Access::Write(address, value));
+    }
+
+    fn tick(&mut self) {
+        self.cycles.push(std::mem::take(&mut self.current));
+    }
+
+    fn peek(&self, address: u16) -> u8 {
+        self.mem.peek(address)
+    }
+
+    fn int_flags(&self) -> u8 {
+        0
+    }
+
+    fn set_int_flags(&mut self, _value: u8) {}
+
+    fn int_enable(&self) -> u8 {
+        0
+    }
+}
+
+const REGS: [&str; 8] = ["a", "b", "c", "d", "e", "f", "h", "l"];
+
+fn field(state: &Value, name: &str) -> u16 {
+    state[name].as_u64().unwrap_or(0) as u16
+}
+
+fn ram(state: &Value) -> Vec<(u16, u8)> {
+    state["ram"]
+        .as_array()
+        .map(|entries| {
+            entries
+                .iter()
+                .map(|e| (e[0].as_u64().unwrap() as u16, e[1].as_u64().unwrap() as u8))
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+fn reg(cpu: &CpuContext<TestBus>, name: &str) -> u16 {
+    match name {
+        "a" => cpu.regs.a as u16,
+        "b" => cpu.regs.b as u16,
+        "c" => cpu.regs.c as u16,
+        "d" => cpu.regs.d as u16,
+        "e" => cpu.regs.e as u16,
+        "f" => cpu.regs.f() as u16,
+        "h" => cpu.regs.h as u16,
+        "l" => cpu.regs.l as u16,
+        "pc" => cpu.regs.pc,
+        "sp" => cpu.regs.sp,
+        "ime" => cpu.int_master_enable as u16,
+        _ => unreachable!(),
+    }
+}
+
+fn set_state(cpu: &mut CpuContext<TestBus>, state: &Value) {
+    cpu.regs.a = field(state, "a") as u8;
+    cpu.regs.b = field(state, "b") as u8;
+    cpu.regs.c = field(state, "c") as u8;
+    cpu.regs.d = field(state, "d") as u8;
+    cpu.regs.e = field(state, "e") as u8;
+    cpu.regs.set_f(field(state, "f") as u8);
+    cpu.regs.h = field(state, "h") as u8;
+    cpu.regs.l = field(state, "l") as u8;
+    cpu.regs.pc = field(state, "pc");
+    cpu.regs.sp = field(state, "sp");
+    cpu.int_master_enable = field(state, "ime") != 0;
+    cpu.enabling_ime = false;
+    cpu.halted = false;
+    cpu.halt_bug = false;
+    cpu.stopped = false;
+
+    for (address, value) in ram(state) {
+        cpu.bus.mem.write(address, value);
+    }
+}
+
+// The access an entry of `cycles` expects, None for an idle bus.
+fn expected_access(cycle: &Value) -> Option<Access> {
+    let address = cycle.get(0)?.as_u64()? as u16;
+    let value = cycle.get(1)?.as_u64()? as u8;
+    let kind = cycle.get(2)?.as_str()?;
+
+    match kind.as_bytes() {
+        [b'r', ..] => Some(Access::Read(address, value)),
+        [_, b'w', ..] => Some(Access::Write(address, value)),
+        _ => None,
+    }
+}
+
+fn describe(accesses: &[Access]) -> String {
+    if accesses.is_empty() {
+        return "idle".to_string();
+    }
+    accesses
+        .iter()
+        .map(|a| a.to_string())
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+// Compares the logged bus activity against the case's `cycles`, one line
+// per M-cycle that differs.
+fn diff_cycles(cpu: &CpuContext<TestBus>, cycles: &[Value]) -> Vec<String> {
+    let mut got = cpu.bus.cycles.clone();
+    // Accesses after the last tick belong to a cycle that never completed.
+    if !cpu.bus.current.is_empty() {
+        got.push(cpu.bus.current.clone());
+    }
+
+    let mut diffs = Vec::new();
+    if got.len() != cycles.len() {
+        diffs.push(format!("cycles: expected {}, got {}", cycles.len(), got.len()));
+    }
+
+    for (i, (want, got)) in cycles.iter().zip(&got).enumerate() {
+        let want: Vec<Access> = expected_access(want).into_iter().collect();
+        if want != *got {
+            diffs.push(format!(
+                "cycle {}: expected {}, got {}",
+                i,
+                describe(&want),
+                describe(got)
+            ));
+        }
+    }
+    diffs
+}
+
+// Runs one case and returns a line per mismatch.
+fn run_case(case: &Value) -> Vec<String> {
+    let mut cpu = CpuContext::new(TestBus::new());
+    set_state(&mut cpu, &case["initial"]);
+
+    let mut diffs = Vec::new();
+    if let Err(err) = cpu.cpu_step() {
+        return vec![err.to_string()];
+    }
+
+    let expected = &case["final"];
+    for name in REGS.iter().chain(&["pc", "sp"]) {
+        let (want, got) = (field(expected, name), reg(&cpu, name));
+        if want != got {
+            diffs.push(format!("{:>3}: expected {:04X}, got {:04X}", name, want, got));
+        }
+    }
+
+    if expected.get("ime").is_some() && field(expected, "ime") != reg(&cpu, "ime") {
+        diffs.push(format!(
+            "ime: expected {}, got {}",
+            field(expected, "ime"),
+            reg(&cpu, "ime")
+        ));
+    }
+
+    for (address, want) in ram(expected) {
+        let got = cpu.bus.peek(address);
+        if want != got {
+            diffs.push(format!("[{:04X}]: expected {:02X}, got {:02X}", address, want, got));
+        }
+    }
+
+    let cycles = case["cycles"].as_array().cloned().unwrap_or_default();
+    diffs.extend(diff_cycles(&cpu, &cycles));
+    diffs
+}
+
+#[test]
+#[ignore = "needs the SM83 JSON test vectors"]
+fn sm83_json_vectors() {
+    let dir = std::env::var_os("SM83_TESTS")
+        .map(PathBuf::from)
+        .unwrap_or_else(|| PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("tests/sm83"));
+
+    let entries = fs::read_dir(&dir)
+        .unwrap_or_else(|err| panic!("no SM83 test vectors in {}: {}", dir.display(), err));
+
+    let mut files: Vec<PathBuf> = entries
+        .filter_map(|e| e.ok().map(|e| e.path()))
+        .filter(|p| p.extension().is_some_and(|ext| ext == "json"))
+        .collect();
+    files.sort();
+
+    let mut failed = Vec::new();
+    for path in &files {
+        let opcode = path.file_stem().unwrap().to_string_lossy().to_string();
+        let cases: Value = serde_json::from_str(&fs::read_to_string(path).unwrap())
+            .unwrap_or_else(|err| panic!("{}: {}", path.display(), err));
+        let cases = cases.as_array().cloned().unwrap_or_default();
+
+        let mut failures = 0;
+        let mut first = None;
+        for case in &cases {
+            let diffs = run_case(case);
+            if !diffs.is_empty() {
+                failures += 1;
+                first.get_or_insert((case["name"].to_string(), diffs));
+            }
+        }
+
+        if let Some((name, diffs)) = first {
+            println!("{:<6} FAIL {}/{}, first: {}", opcode, failures, cases.len(), name);
+            for diff in diffs {
+                println!("         {}", diff);
+            }
+            failed.push(opcode);
+        } else {
+            println!("{:<6} ok   {}", opcode, cases.len());
+        }
+    }
+
+    assert!(failed.is_empty(), "failing opcodes: {}", failed.join(", "));
+}
@@ -0,0 +1,179 @@
+mod common;
+
+use std::cell::RefCell;
+use std::io::{self, Cursor, Write};
+use std::rc::Rc;
+
+use gameboy::bus::{Bus, BusContext, FlatMemory};
+use gameboy::cart::CartContext;
+use gameboy::cpu::CpuContext;
+use gameboy::error::EmuError;
+use gameboy::model::Model;
+use gameboy::trace::{
+    parse_frame_range, parse_pc_range, TraceCompareContext, TraceContext, TraceFormat,
+};
+
+// Trace output shared with the test, the tracer owns its writer.
+#[derive(Clone, Default)]
+struct SharedBuffer(Rc<RefCell<Vec<u8>>>);
+
+impl SharedBuffer {
+    fn lines(&self) -> Vec<String> {
+        String::from_utf8_lossy(&self.0.borrow())
+            .lines()
+            .map(String::from)
+            .collect()
+    }
+}
+
+impl Write for SharedBuffer {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.0.borrow_mut().extend_from_slice(buf);
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+// NOP ; LD B,$42 ; INC B ; NOP
+const PROGRAM: [u8; 5] = [0x00, 0x06, 0x42, 0x04, 0x00];
+
+const DOCTOR_LINES: [&str; 4] = [
+    "A:01 F:B0 B:00 C:13 D:00 E:D8 H:01 L:4D SP:FFFE PC:C000 PCMEM:00,06,42,04",
+    "A:01 F:B0 B:00 C:13 D:00 E:D8 H:01 L:4D SP:FFFE PC:C001 PCMEM:06,42,04,00",
+    "A:01 F:B0 B:42 C:13 D:00 E:D8 H:01 L:4D SP:FFFE PC:C003 PCMEM:04,00,00,00",
+    "A:01 F:10 B:43 C:13 D:00 E:D8 H:01 L:4D SP:FFFE PC:C004 PCMEM:00,00,00,00",
+];
+
+// Registers as the DMG boot ROM leaves them.
+fn cpu_after_boot<B: Bus>(bus: B) -> CpuContext<B> {
+    let mut cpu = CpuContext::new(bus);
+    cpu.regs.a = 0x01;
+    cpu.regs.set_f(0xB0);
+    cpu.regs.set_bc(0x0013);
+    cpu.regs.set_de(0x00D8);
+    cpu.regs.set_hl(0x014D);
+    cpu.regs.sp = 0xFFFE;
+    cpu
+}
+
+fn traced_cpu<B: Bus>(bus: B, out: &SharedBuffer) -> CpuContext<B> {
+    let mut cpu = cpu_after_boot(bus);
+    cpu.tracer = Some(TraceContext::new(TraceFormat::Doctor, Box::new(out.clone())));
+    cpu
+}
+
+#[test]
+fn doctor_lines() {
+    let out = SharedBuffer::default();
+    let mut cpu = traced_cpu(FlatMemory::new(), &out);
+    common::run(&mut cpu, &PROGRAM, 4);
+
+    assert_eq!(out.lines(), DOCTOR_LINES);
+}
+
+#[test]
+fn pc_range() {
+    let out = SharedBuffer::default();
+    let mut cpu = traced_cpu(FlatMemory::new(), &out);
+    cpu.tracer.as_mut().unwrap().pc_range = parse_pc_range("C001-C003");
+    common::run(&mut cpu, &PROGRAM, 4);
+
+    assert_eq!(out.lines(), DOCTOR_LINES[1..3]);
+}
+
+#[test]
+fn frame_range() {
+    let out = SharedBuffer::default();
+    let mut cart = CartContext::new();
+    let mut cpu = traced_cpu(BusContext::new(&mut cart, Model::Dmg), &out);
+    cpu.tracer.as_mut().unwrap().frame_range = parse_frame_range("2-3");
+
+    cpu.bus.ppu.current_frame = 1;
+    common::run(&mut cpu, &PROGRAM, 2);
+    assert!(out.lines().is_empty());
+
+    cpu.bus.ppu.current_frame = 2;
+    cpu.cpu_step().unwrap();
+    assert_eq!(out.lines(), DOCTOR_LINES[2..3]);
+
+    cpu.bus.ppu.current_frame = 4;
+    cpu.cpu_step().unwrap();
+    assert_eq!(out.lines().len(), 1);
+}
+
+#[test]
+fn parse_ranges() {
+    assert_eq!(parse_pc_range("0100-01FF"), Some(0x0100..=0x01FF));
+    assert_eq!(parse_pc_range("0x0150-0x0150"), Some(0x0150..=0x0150));
+    assert_eq!(parse_frame_range("60-120"), Some(60..=120));
+    assert_eq!(parse_pc_range("0200-0100"), None);
+    assert_eq!(parse_pc_range("0100"), None);
+    assert_eq!(parse_frame_range("a-b"), None);
+}
+
+#[test]
+fn compare_mismatch() {
+    // The third line expects B:41 where the CPU has B:42.
+    let mut reference = DOCTOR_LINES.map(String::from);
+    reference[2] = reference[2].replace("B:42", "B:41");
+    let reference = Cursor::new(reference.join("\n"));
+
+    let mut cpu = cpu_after_boot(FlatMemory::new());
+    cpu.trace_compare = Some(TraceCompareContext::new(Box::new(reference), 16));
+    common::run(&mut cpu, &PROGRAM, 2);
+
+    // The diverging instruction doesn't run.
+    let mismatch = match cpu.cpu_step() {
+        Err(EmuError::TraceMismatch(mismatch)) => mismatch,
+        other => panic!("expected a trace mismatch, got {:?}", other),
+    };
+    assert_eq!(cpu.regs.pc, 0xC003);
+    assert_eq!(cpu.regs.b, 0x42);
+
+    assert_eq!(mismatch.line, 3);
+    assert_eq!(mismatch.expected, DOCTOR_LINES[2].replace("B:42", "B:41"));
+    assert_eq!(mismatch.actual, DOCTOR_LINES[2]);
+    assert_eq!(mismatch.history, ["C000: NOP", "C001: LD B,$42"]);
+    assert_eq!(mismatch.pc, 0xC003);
+    assert_eq!(mismatch.memory[8..12], [0x04, 0x00, 0x00, 0x00]);
+
+    let report = mismatch.to_string();
+    let lines: Vec<_> = report.lines().collect();
+    assert_eq!(
+        lines,
+        [
+            "trace diverged at reference line 3",
+            &format!("  expected: {}", mismatch.expected),
+            &format!("  actual:   {}", mismatch.actual),
+            "    B:42 (expected B:41)",
+            "last 2 instructions:",
+            "  C000: NOP",
+            "  C001: LD B,$42",
+            "memory at BFFB: 00 00 00 00 00 00 06 42>04 00 00 00 00 00 00 00",
+        ]
+    );
+}
+
+#[test]
+fn compare_history_depth() {
+    // The mismatch is on the last line, only the last 2 instructions are
+    // kept.
+    let mut reference = DOCTOR_LINES.map(String::from);
+    reference[3] = reference[3].replace("F:10", "F:90");
+    let reference = Cursor::new(reference.join("\n"));
+
+    let mut cpu = cpu_after_boot(FlatMemory::new());
+    cpu.trace_compare = Some(TraceCompareContext::new(Box::new(reference), 2));
+    common::run(&mut cpu, &PROGRAM, 3);
+
+    match cpu.cpu_step() {
+        Err(EmuError::TraceMismatch(mismatch)) => {
+            assert_eq!(mismatch.line, 4);
+            assert_eq!(mismatch.history, ["C001: LD B,$42", "C003: INC B"]);
+        }
+        other => panic!("expected a trace mismatch, got {:?}", other),
+    }
+}